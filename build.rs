@@ -0,0 +1,100 @@
+//! Checks the bundled dictionaries and writes them out as Rust, along with
+//! their lookup tables, so the game doesn't have to parse or index anything
+//! when it starts. A broken entry fails the build here instead of panicking
+//! on the first lookup.
+
+use std::{collections::HashMap, env, fmt::Write, fs, path::Path};
+
+use serde_json::Value;
+
+#[allow(dead_code)]
+#[path = "src/entry.rs"]
+mod entry;
+#[allow(dead_code)]
+#[path = "src/table.rs"]
+mod table;
+
+use entry::Entry;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/entry.rs");
+    println!("cargo:rerun-if-changed=src/table.rs");
+    let mut out = String::new();
+    for (name, file) in [
+        ("DICTIONARY", "src/dictionary.json"),
+        ("HARD_DICTIONARY", "src/hard_dictionary.json"),
+        ("GUESSES", "src/guesses.json"),
+    ] {
+        println!("cargo:rerun-if-changed={}", file);
+        let path = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(file);
+        let json = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", file, e));
+        let entries = read(&json).unwrap_or_else(|e| panic!("{}: {}", file, e));
+        write(&mut out, name, &entries);
+    }
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("dictionaries.rs");
+    fs::write(path, out).unwrap();
+}
+
+/// Reads a dictionary file the way `Dictionary::parse` does, except a word
+/// we can't play is an error instead of being dropped. Duplicates are merged
+/// into the first one.
+fn read(json: &str) -> Result<Vec<Entry>, String> {
+    let values: Vec<Value> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (i, v) in values.iter().enumerate() {
+        let mut entry = Entry::parse(v).map_err(|e| format!("entry {}: {}", i, e))?;
+        if !entry.is_playable() {
+            return Err(format!("entry {}: \"{}\" can't be played", i, entry.word));
+        }
+        entry.word = entry.word.to_lowercase().into();
+        match index.get(&*entry.word) {
+            Some(&first) => {
+                let existing = &mut entries[first];
+                existing.tags.to_mut().extend(entry.tags.iter());
+                existing.tags.to_mut().sort();
+                existing.tags.to_mut().dedup();
+                existing.description = existing.description.take().or(entry.description);
+                existing.example = existing.example.take().or(entry.example);
+            }
+            None => {
+                index.insert(entry.word.to_string(), entries.len());
+                entries.push(entry);
+            }
+        }
+    }
+    Ok(entries)
+}
+
+fn write(out: &mut String, name: &str, entries: &[Entry]) {
+    writeln!(
+        out,
+        "static {}_ENTRIES: [Entry; {}] = [",
+        name,
+        entries.len()
+    )
+    .unwrap();
+    for e in entries {
+        let tags: Vec<String> = e.tags.iter().map(|t| format!("Tag::{:?}", t)).collect();
+        writeln!(
+            out,
+            "    Entry::bundled({:?}, &[{}], {:?}, {:?}),",
+            e.word,
+            tags.join(", "),
+            e.description,
+            e.example
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    let slots = table::build(entries.iter().map(|e| &*e.word));
+    writeln!(
+        out,
+        "static {}_TABLE: [u32; {}] = {:?};",
+        name,
+        slots.len(),
+        slots
+    )
+    .unwrap();
+}
@@ -0,0 +1,393 @@
+//! Looks after the word lists: checks them for mistakes, tidies them up,
+//! merges new words in, tags them and prints what's in them. Run it with
+//! `cargo run --bin codle-dict -- <command>`.
+//!
+//! Order matters in the answer lists, since the daily schedule is shuffled
+//! from it, so nothing here reorders a file unless asked to with `--sort`.
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    path::{Path, PathBuf},
+    process,
+};
+
+use codle::{
+    dictionary::{Dictionary, Entry, Tag},
+    game::{MAX_WORD_LENGTH, MIN_WORD_LENGTH},
+};
+use serde_json::Value;
+
+const USAGE: &str = "usage: codle-dict <command>
+
+  lint [ANSWERS...] [--guesses GUESSES...]
+      checks for words that can't be played, duplicates, answers without tags
+      or a description, and guesses that are answers too. with no files it
+      checks the bundled dictionaries. only words that aren't ASCII, or
+      files that don't parse, count as errors
+  fmt [--sort] FILE...
+      lowercases every word, merges duplicates and writes the file back out
+  merge INTO FROM...
+      adds every word from FROM to INTO, merging tags and notes
+  tag FILE TAG WORD...
+      adds TAG to each WORD
+  stats [--length N] FILE...
+      counts words by length and tag, and how often each letter comes up";
+
+/// Where the bundled dictionaries are.
+fn bundled(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join(name)
+}
+
+/// Every entry in a dictionary file, as it's written.
+fn read(path: &Path) -> Result<Vec<Entry>, Box<dyn Error>> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let values: Vec<Value> =
+        serde_json::from_str(&json).map_err(|e| format!("{}: {}", path.display(), e))?;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            Entry::parse(v)
+                .map_err(|e| format!("{}: entry {}: {}", path.display(), i, e).into())
+        })
+        .collect()
+}
+
+fn write(path: &Path, entries: &[Entry]) -> Result<(), Box<dyn Error>> {
+    let lines: Vec<String> = entries.iter().map(|e| e.to_json()).collect();
+    std::fs::write(path, format!("[\n{}\n]\n", lines.join(",\n")))?;
+    Ok(())
+}
+
+/// Lowercases the entry if it's one we play. Anything else (like `SETcc`)
+/// keeps its case so it stays unplayable.
+fn normalise(mut entry: Entry) -> Entry {
+    if entry.is_playable() {
+        entry.word = entry.word.to_lowercase().into();
+    }
+    entry
+}
+
+/// Why a word can't be played, if it can't.
+fn unplayable(entry: &Entry) -> Option<String> {
+    let length = entry.word.chars().count();
+    if entry.is_playable() {
+        None
+    } else if !entry.word.chars().all(|c| c.is_ascii_alphabetic()) {
+        Some("isn't a plain word".to_string())
+    } else if !(MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&length) {
+        Some(format!("has {} letters", length))
+    } else {
+        Some("is mixed case".to_string())
+    }
+}
+
+#[derive(Default)]
+struct Lint {
+    errors: usize,
+    warnings: usize,
+}
+
+impl Lint {
+    fn error(&mut self, path: &Path, i: usize, word: &str, problem: &str) {
+        println!(
+            "error: {}: entry {} \"{}\" {}",
+            path.display(),
+            i,
+            word,
+            problem
+        );
+        self.errors += 1;
+    }
+
+    fn warn(&mut self, path: &Path, i: usize, word: &str, problem: &str) {
+        println!(
+            "warning: {}: entry {} \"{}\" {}",
+            path.display(),
+            i,
+            word,
+            problem
+        );
+        self.warnings += 1;
+    }
+
+    /// Checks one file on its own. Returns the words it'd play, and where.
+    fn file(
+        &mut self,
+        path: &Path,
+        answers: bool,
+    ) -> Result<HashMap<String, usize>, Box<dyn Error>> {
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for (i, entry) in read(path)?.iter().enumerate() {
+            let word = &entry.word;
+            if !word.is_ascii() {
+                self.error(path, i, word, "has characters that aren't ASCII");
+                continue;
+            }
+            if let Some(reason) = unplayable(entry) {
+                self.warn(path, i, word, &format!("{}, so it's never played", reason));
+                continue;
+            }
+            let key = word.to_lowercase();
+            if let Some(j) = seen.get(&key) {
+                self.warn(path, i, word, &format!("is already entry {}", j));
+                continue;
+            }
+            seen.insert(key, i);
+            if answers && entry.tags.is_empty() {
+                self.warn(path, i, word, "has no tags");
+            }
+            if answers && entry.description.is_none() {
+                self.warn(path, i, word, "has no description");
+            }
+        }
+        Ok(seen)
+    }
+}
+
+fn lint(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (mut answers, mut guesses) = (Vec::new(), Vec::new());
+    let mut list = &mut answers;
+    for arg in args {
+        if arg == "--guesses" {
+            list = &mut guesses;
+        } else {
+            list.push(PathBuf::from(arg));
+        }
+    }
+    if answers.is_empty() && guesses.is_empty() {
+        answers = vec![bundled("dictionary.json"), bundled("hard_dictionary.json")];
+        guesses = vec![bundled("guesses.json")];
+    }
+
+    let mut lint = Lint::default();
+    let mut answer_words = Vec::new();
+    for path in &answers {
+        answer_words.push((path, lint.file(path, true)?));
+    }
+    for path in &guesses {
+        let words = lint.file(path, false)?;
+        let mut words: Vec<_> = words.into_iter().collect();
+        words.sort_by_key(|(_, i)| *i);
+        for (word, i) in words {
+            for (answer_path, answer_words) in &answer_words {
+                if answer_words.contains_key(&word) {
+                    let problem = format!("is an answer in {} too", answer_path.display());
+                    lint.warn(path, i, &word, &problem);
+                }
+            }
+        }
+    }
+
+    println!("{} errors, {} warnings", lint.errors, lint.warnings);
+    if lint.errors > 0 {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn fmt(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let sort = args.iter().any(|a| a == "--sort");
+    for path in args.iter().filter(|a| *a != "--sort").map(PathBuf::from) {
+        let entries = read(&path)?;
+        let before = entries.len();
+        let mut dictionary = Dictionary::default();
+        for entry in entries {
+            dictionary.insert(normalise(entry));
+        }
+        let mut entries = dictionary.entries.into_owned();
+        if sort {
+            entries.sort_by_key(|e| e.word.to_lowercase());
+        }
+        write(&path, &entries)?;
+        println!(
+            "{}: {} entries, {} duplicates merged",
+            path.display(),
+            entries.len(),
+            before - entries.len()
+        );
+    }
+    Ok(())
+}
+
+fn merge(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let [into, from @ ..] = args else {
+        return Err(USAGE.into());
+    };
+    if from.is_empty() {
+        return Err(USAGE.into());
+    }
+    let into = PathBuf::from(into);
+    let mut dictionary = Dictionary::default();
+    if into.exists() {
+        for entry in read(&into)? {
+            dictionary.insert(normalise(entry));
+        }
+    }
+    let before = dictionary.entries.len();
+    for path in from.iter().map(PathBuf::from) {
+        for entry in read(&path)? {
+            dictionary.insert(normalise(entry));
+        }
+    }
+    write(&into, &dictionary.entries)?;
+    println!(
+        "{}: {} new words, {} in total",
+        into.display(),
+        dictionary.entries.len() - before,
+        dictionary.entries.len()
+    );
+    Ok(())
+}
+
+fn tag(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let [path, tag, words @ ..] = args else {
+        return Err(USAGE.into());
+    };
+    let path = PathBuf::from(path);
+    let tag = Tag::from_id(tag).ok_or_else(|| {
+        let ids: Vec<&str> = Tag::ALL.iter().map(|t| t.id()).collect();
+        format!("unknown tag \"{}\", try one of {}", tag, ids.join(", "))
+    })?;
+    let mut entries = read(&path)?;
+    let mut missing = Vec::new();
+    for word in words {
+        let mut found = false;
+        for entry in entries.iter_mut() {
+            if entry.word.eq_ignore_ascii_case(word) {
+                found = true;
+                if !entry.tags.contains(&tag) {
+                    entry.tags.to_mut().push(tag);
+                    entry.tags.to_mut().sort();
+                }
+            }
+        }
+        if !found {
+            missing.push(word.as_str());
+        }
+    }
+    write(&path, &entries)?;
+    if !missing.is_empty() {
+        return Err(format!("not in {}: {}", path.display(), missing.join(", ")).into());
+    }
+    Ok(())
+}
+
+fn stats(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut length = None;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--length" {
+            let n = args.next().and_then(|n| n.parse::<usize>().ok());
+            length = Some(n.ok_or("--length wants a number")?);
+        } else {
+            paths.push(PathBuf::from(arg));
+        }
+    }
+    if paths.is_empty() {
+        return Err(USAGE.into());
+    }
+
+    for path in paths {
+        let json = std::fs::read_to_string(&path)?;
+        // counted the way the game sees it
+        let dictionary =
+            Dictionary::parse(&json).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let entries: Vec<&Entry> = dictionary
+            .entries
+            .iter()
+            .filter(|e| length.is_none() || length == Some(e.word.len()))
+            .collect();
+        println!("{}: {} words", path.display(), entries.len());
+
+        let lengths: Vec<String> = (MIN_WORD_LENGTH..=MAX_WORD_LENGTH)
+            .map(|n| (n, entries.iter().filter(|e| e.word.len() == n).count()))
+            .filter(|(_, count)| *count > 0)
+            .map(|(n, count)| format!("{}: {}", n, count))
+            .collect();
+        println!("  by length: {}", lengths.join(", "));
+        println!(
+            "  {} described, {} with an example",
+            entries.iter().filter(|e| e.description.is_some()).count(),
+            entries.iter().filter(|e| e.example.is_some()).count()
+        );
+
+        let mut tags: Vec<(Tag, usize)> = Tag::ALL
+            .iter()
+            .map(|t| (*t, entries.iter().filter(|e| e.tags.contains(t)).count()))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        tags.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        if !tags.is_empty() {
+            let tags: Vec<String> = tags
+                .iter()
+                .map(|(t, count)| format!("{} {}", t.id(), count))
+                .collect();
+            println!("  tags: {}", tags.join(", "));
+        }
+
+        let mut letters = [0usize; 26];
+        for entry in &entries {
+            for c in entry.word.bytes() {
+                letters[(c - b'a') as usize] += 1;
+            }
+        }
+        let total: usize = letters.iter().sum();
+        let mut order: Vec<usize> = (0..26).filter(|i| letters[*i] > 0).collect();
+        order.sort_by(|a, b| letters[*b].cmp(&letters[*a]));
+        println!("  letters:");
+        for i in order {
+            let share = letters[i] as f64 / total as f64;
+            println!(
+                "    {} {:5.1}% {}",
+                (b'a' + i as u8) as char,
+                share * 100.0,
+                "#".repeat((share * 200.0).round() as usize)
+            );
+        }
+
+        // where each letter tends to go only makes sense for one length
+        if let Some(n) = length {
+            println!("  most common by position:");
+            for position in 0..n {
+                let mut counts = [0usize; 26];
+                for entry in &entries {
+                    counts[(entry.word.as_bytes()[position] - b'a') as usize] += 1;
+                }
+                let mut order: Vec<usize> = (0..26).filter(|i| counts[*i] > 0).collect();
+                order.sort_by(|a, b| counts[*b].cmp(&counts[*a]));
+                let top: Vec<String> = order
+                    .iter()
+                    .take(5)
+                    .map(|i| format!("{} {}", (b'a' + *i as u8) as char, counts[*i]))
+                    .collect();
+                println!("    {}: {}", position + 1, top.join(", "));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, args)) = args.split_first() else {
+        println!("{}", USAGE);
+        return;
+    };
+    let result = match command.as_str() {
+        "lint" => lint(args),
+        "fmt" => fmt(args),
+        "merge" => merge(args),
+        "tag" => tag(args),
+        "stats" => stats(args),
+        _ => Err(USAGE.into()),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
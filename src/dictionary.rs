@@ -0,0 +1,476 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
+
+use serde_json::Value;
+
+pub use crate::entry::{Entry, Tag};
+use crate::{
+    game::{Mode, Settings},
+    pack::{self, Pack},
+    schedule, table,
+};
+
+// the bundled dictionaries, checked and turned into tables by build.rs
+include!(concat!(env!("OUT_DIR"), "/dictionaries.rs"));
+
+pub static DICTIONARY: Dictionary = Dictionary::bundled(&DICTIONARY_ENTRIES, &DICTIONARY_TABLE);
+pub static HARD_DICTIONARY: Dictionary =
+    Dictionary::bundled(&HARD_DICTIONARY_ENTRIES, &HARD_DICTIONARY_TABLE);
+/// Everything else we take as a guess: ordinary English words plus
+/// programming vocabulary that isn't curated enough to be an answer.
+pub static GUESSES: Dictionary = Dictionary::bundled(&GUESSES_ENTRIES, &GUESSES_TABLE);
+
+/// Restricts answers to words from a few languages, like "SQL only" or
+/// everything web.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Filter {
+    /// Used as the key for anything saved per filter, and for seeding.
+    pub id: &'static str,
+    pub name: &'static str,
+    /// A word matches if it has any of these.
+    pub tags: &'static [Tag],
+}
+
+impl Filter {
+    pub fn matches(&self, entry: &Entry) -> bool {
+        entry.tags.iter().any(|t| self.tags.contains(t))
+    }
+
+    /// The filter after this one, or none after the last.
+    pub fn next(filter: Option<&'static Filter>) -> Option<&'static Filter> {
+        match filter {
+            None => FILTERS.first(),
+            Some(f) => {
+                let i = FILTERS.iter().position(|g| g == f).unwrap();
+                FILTERS.get(i + 1)
+            }
+        }
+    }
+}
+
+pub const FILTERS: &[Filter] = &[
+    Filter {
+        id: "web",
+        name: "Web (HTML, CSS, JS)",
+        tags: &[Tag::Html, Tag::Css, Tag::JavaScript],
+    },
+    Filter {
+        id: "backend",
+        name: "SQL and shell",
+        tags: &[Tag::Sql, Tag::Bash, Tag::PowerShell],
+    },
+    Filter {
+        id: "systems",
+        name: "Systems (C, C++, Rust, Go)",
+        tags: &[Tag::C, Tag::Cpp, Tag::Rust, Tag::Go],
+    },
+    Filter {
+        id: "managed",
+        name: "Java, Kotlin and C#",
+        tags: &[Tag::Java, Tag::Kotlin, Tag::CSharp],
+    },
+    Filter {
+        id: "scripting",
+        name: "Scripting (Python, Ruby, Lua, PHP)",
+        tags: &[Tag::Python, Tag::Ruby, Tag::Lua, Tag::Php],
+    },
+    Filter {
+        id: "mobile",
+        name: "Mobile (Swift, Dart, Kotlin)",
+        tags: &[Tag::Swift, Tag::Dart, Tag::Kotlin],
+    },
+    Filter {
+        id: "javascript",
+        name: "JavaScript only",
+        tags: &[Tag::JavaScript],
+    },
+    Filter {
+        id: "html",
+        name: "HTML only",
+        tags: &[Tag::Html],
+    },
+    Filter {
+        id: "css",
+        name: "CSS only",
+        tags: &[Tag::Css],
+    },
+    Filter {
+        id: "python",
+        name: "Python only",
+        tags: &[Tag::Python],
+    },
+    Filter {
+        id: "sql",
+        name: "SQL only",
+        tags: &[Tag::Sql],
+    },
+    Filter {
+        id: "bash",
+        name: "Bash only",
+        tags: &[Tag::Bash],
+    },
+    Filter {
+        id: "java",
+        name: "Java only",
+        tags: &[Tag::Java],
+    },
+    Filter {
+        id: "csharp",
+        name: "C# only",
+        tags: &[Tag::CSharp],
+    },
+    Filter {
+        id: "c",
+        name: "C only",
+        tags: &[Tag::C],
+    },
+    Filter {
+        id: "cpp",
+        name: "C++ only",
+        tags: &[Tag::Cpp],
+    },
+    Filter {
+        id: "php",
+        name: "PHP only",
+        tags: &[Tag::Php],
+    },
+    Filter {
+        id: "powershell",
+        name: "PowerShell only",
+        tags: &[Tag::PowerShell],
+    },
+    Filter {
+        id: "go",
+        name: "Go only",
+        tags: &[Tag::Go],
+    },
+    Filter {
+        id: "rust",
+        name: "Rust only",
+        tags: &[Tag::Rust],
+    },
+    Filter {
+        id: "kotlin",
+        name: "Kotlin only",
+        tags: &[Tag::Kotlin],
+    },
+    Filter {
+        id: "ruby",
+        name: "Ruby only",
+        tags: &[Tag::Ruby],
+    },
+    Filter {
+        id: "lua",
+        name: "Lua only",
+        tags: &[Tag::Lua],
+    },
+    Filter {
+        id: "dart",
+        name: "Dart only",
+        tags: &[Tag::Dart],
+    },
+    Filter {
+        id: "swift",
+        name: "Swift only",
+        tags: &[Tag::Swift],
+    },
+    Filter {
+        id: "x86",
+        name: "x86 only",
+        tags: &[Tag::X86],
+    },
+    Filter {
+        id: "arm64",
+        name: "ARM64 only",
+        tags: &[Tag::Arm64],
+    },
+    Filter {
+        id: "ppc",
+        name: "PowerPC only",
+        tags: &[Tag::PowerPc],
+    },
+];
+
+/// A list of words along with what we know about each of them.
+#[derive(Clone, Debug)]
+pub struct Dictionary {
+    pub entries: Cow<'static, [Entry]>,
+    /// Where each word is in `entries`, see [`table`].
+    slots: Cow<'static, [u32]>,
+}
+
+impl Default for Dictionary {
+    fn default() -> Self {
+        Self {
+            entries: Cow::Borrowed(&[]),
+            slots: Cow::Borrowed(&[0]),
+        }
+    }
+}
+
+impl Dictionary {
+    const fn bundled(entries: &'static [Entry], slots: &'static [u32]) -> Self {
+        Self {
+            entries: Cow::Borrowed(entries),
+            slots: Cow::Borrowed(slots),
+        }
+    }
+
+    /// Parses a dictionary file, see [`Entry::parse`]. Every entry is either
+    /// just the word, or an object like `{"word": "yield", "tags": ["python", "rust"],
+    /// "description": "...", "example": "..."}`.
+    ///
+    /// Everything is lowercased so dictionaries can be compared with each
+    /// other, and anything that isn't a plain word of a length we can play
+    /// (like `INT n` or `SETcc`) is dropped, where build.rs would refuse it.
+    /// A word that shows up twice gets the tags of both.
+    pub fn parse(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let values: Vec<Value> = serde_json::from_str(json)?;
+        let mut dictionary = Dictionary::default();
+        for (i, v) in values.iter().enumerate() {
+            let mut entry = Entry::parse(v).map_err(|e| format!("entry {}: {}", i, e))?;
+            if !entry.is_playable() {
+                continue;
+            }
+            entry.word = entry.word.to_lowercase().into();
+            dictionary.insert(entry);
+        }
+        Ok(dictionary)
+    }
+
+    /// Adds a (lowercase) entry, or merges it into the one that's already
+    /// there for the same word.
+    pub fn insert(&mut self, entry: Entry) {
+        match self.position(&entry.word) {
+            Some(i) => {
+                let existing = &mut self.entries.to_mut()[i];
+                for tag in entry.tags.iter() {
+                    if !existing.tags.contains(tag) {
+                        existing.tags.to_mut().push(*tag);
+                    }
+                }
+                existing.tags.to_mut().sort();
+                if existing.description.is_none() {
+                    existing.description = entry.description;
+                }
+                if existing.example.is_none() {
+                    existing.example = entry.example;
+                }
+            }
+            None => {
+                let i = self.entries.len();
+                self.entries.to_mut().push(entry);
+                if self.entries.len() * 2 > self.slots.len() {
+                    self.slots = table::build(self.entries.iter().map(|e| &*e.word)).into();
+                } else {
+                    table::insert(self.slots.to_mut(), &self.entries[i].word, i);
+                }
+            }
+        }
+    }
+
+    fn position(&self, word: &str) -> Option<usize> {
+        table::find(&self.slots, word, |i, word| {
+            self.entries[i].word.eq_ignore_ascii_case(word)
+        })
+    }
+
+    /// Looks a word up, in any case.
+    pub fn get(&self, word: &str) -> Option<&Entry> {
+        self.position(word).map(|i| &self.entries[i])
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.get(word).is_some()
+    }
+}
+
+/// Every entry for a word across the bundled dictionaries and the answers of
+/// any loaded packs. A word like `yield` is in both bundled ones, as a
+/// keyword and as an ARM64 instruction.
+pub fn lookup(word: &str) -> Vec<&'static Entry> {
+    [&DICTIONARY, &HARD_DICTIONARY]
+        .into_iter()
+        .chain(pack::all().into_iter().map(|p| &p.answers))
+        .filter_map(|d| d.get(word))
+        .collect()
+}
+
+/// Which words the answer gets picked from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum WordList {
+    /// Keywords and such from `dictionary.json`.
+    #[default]
+    Standard,
+    /// x86/ARM64/PPC mnemonics from `hard_dictionary.json`.
+    Assembly,
+    /// Both of the above.
+    Mixed,
+    /// Someone's own words, see [`pack`].
+    Pack(&'static Pack),
+}
+
+impl WordList {
+    /// The bundled lists. Packs come after these.
+    pub const ALL: [WordList; 3] = [WordList::Standard, WordList::Assembly, WordList::Mixed];
+
+    /// Used as the key for anything saved per word list.
+    pub fn id(&self) -> &'static str {
+        match self {
+            WordList::Standard => "standard",
+            WordList::Assembly => "assembly",
+            WordList::Mixed => "mixed",
+            WordList::Pack(p) => &p.id,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WordList::Standard => "Programming",
+            WordList::Assembly => "Assembly",
+            WordList::Mixed => "Mixed",
+            WordList::Pack(p) => &p.name,
+        }
+    }
+
+    /// The answers of a pack all have the same length.
+    pub fn word_length(&self) -> Option<usize> {
+        match self {
+            WordList::Pack(p) => Some(p.word_length),
+            _ => None,
+        }
+    }
+
+    /// Goes through the bundled lists and then every loaded pack.
+    pub fn next(&self) -> WordList {
+        let lists: Vec<WordList> = WordList::ALL
+            .into_iter()
+            .chain(pack::all().into_iter().map(WordList::Pack))
+            .collect();
+        let i = lists.iter().position(|l| l == self).unwrap_or(0);
+        lists[(i + 1) % lists.len()]
+    }
+
+    /// Mixed into the schedule seed so every list gets its own word of the day.
+    fn seed_offset(&self) -> u64 {
+        match self {
+            WordList::Standard => 0,
+            WordList::Assembly => 1_000_000,
+            WordList::Mixed => 2_000_000,
+            WordList::Pack(p) => schedule::seed(&p.id.bytes().map(u64::from).collect::<Vec<_>>()),
+        }
+    }
+
+    /// Every possible answer of the given length, only counting words the
+    /// filter matches if there is one.
+    pub fn answers(&self, length: usize, filter: Option<&Filter>) -> Vec<&'static str> {
+        let lists: &[&'static Dictionary] = match self {
+            WordList::Standard => &[&DICTIONARY],
+            WordList::Assembly => &[&HARD_DICTIONARY],
+            WordList::Mixed => &[&DICTIONARY, &HARD_DICTIONARY],
+            WordList::Pack(p) => &[&p.answers],
+        };
+        let mut words: Vec<&'static str> = Vec::new();
+        for e in lists.iter().flat_map(|l| l.entries.iter()) {
+            if e.word.len() == length
+                && filter.is_none_or(|f| f.matches(e))
+                && !words.contains(&&*e.word)
+            {
+                words.push(&e.word);
+            }
+        }
+        words
+    }
+
+    /// Whether `word` is something we accept as a guess: any answer or
+    /// anything in [`GUESSES`], plus a pack's own guesses. Mnemonics are only
+    /// accepted when they can also be the answer.
+    pub fn is_valid(&self, word: &str) -> bool {
+        if DICTIONARY.contains(word) || GUESSES.contains(word) {
+            return true;
+        }
+        match self {
+            WordList::Standard => false,
+            WordList::Assembly | WordList::Mixed => HARD_DICTIONARY.contains(word),
+            WordList::Pack(p) => p.answers.contains(word) || p.guesses.contains(word),
+        }
+    }
+
+    /// Every word [`WordList::is_valid`] takes with this many letters.
+    pub fn guesses(&self, length: usize) -> Vec<&'static str> {
+        let lists: &[&'static Dictionary] = match self {
+            WordList::Standard => &[&DICTIONARY, &GUESSES],
+            WordList::Assembly | WordList::Mixed => &[&DICTIONARY, &GUESSES, &HARD_DICTIONARY],
+            WordList::Pack(p) => &[&DICTIONARY, &GUESSES, &p.answers, &p.guesses],
+        };
+        let mut seen = HashSet::new();
+        lists
+            .iter()
+            .flat_map(|l| l.entries.iter())
+            .map(|e| &*e.word)
+            .filter(|w| w.len() == length && seen.insert(*w))
+            .collect()
+    }
+}
+
+/// Picks answer `k` (counting from 1) for these settings. The daily word is
+/// whatever [`schedule`] has lined up for `day`; multi-board days take the
+/// next few entries as a [`schedule::group`], so they never share a word
+/// either. Infinite and
+/// practice games get their own schedule, seeded by the day and the practice
+/// seed respectively.
+pub fn get_word(k: i64, day: u64, settings: &Settings, words: &mut HashMap<i64, String>) -> String {
+    if let Some(w) = words.get(&k) {
+        w.clone()
+    } else {
+        let index = (k.max(1) - 1) as u64;
+        let boards = settings.boards as u64;
+        let (salt, game) = match settings.mode {
+            Mode::Daily => (0, schedule::puzzle_number(day) - 1 + index / boards),
+            Mode::Infinite => ((1 << 32) | day, index / boards),
+            Mode::Practice => ((2 << 32) | settings.seed, index / boards),
+        };
+        let mut parts = vec![
+            settings.word_list.seed_offset(),
+            settings.word_length as u64,
+            salt,
+        ];
+        // filtered games get their own schedule, so each filter has its own
+        // word of the day
+        if let Some(filter) = settings.filter {
+            parts.extend(filter.id.bytes().map(u64::from));
+        }
+        let seed = schedule::seed(&parts);
+        let answers = settings.answers();
+        let picks = schedule::group(answers.len(), seed, game, settings.boards);
+        let w = answers[picks[(index % boards) as usize]].to_uppercase();
+
+        words.insert(k, w.clone());
+        w
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::LAUNCH_DAY;
+
+    #[test]
+    fn multi_board_days_never_share_a_word() {
+        let settings = Settings {
+            boards: 8,
+            ..Default::default()
+        };
+        for day in LAUNCH_DAY..LAUNCH_DAY + 2000 {
+            let mut words = HashMap::new();
+            let mut answers: Vec<String> = (1..=8)
+                .map(|k| get_word(k, day, &settings, &mut words))
+                .collect();
+            answers.sort();
+            answers.dedup();
+            assert_eq!(answers.len(), 8, "day {}", day);
+        }
+    }
+}
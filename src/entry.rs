@@ -0,0 +1,239 @@
+//! Dictionary entries and their tags, and reading them from a dictionary
+//! file. `build.rs` includes this file too, like [`table`], so the bundled
+//! dictionaries get read by exactly the same code as anything loaded at
+//! runtime.
+//!
+//! [`table`]: crate::table
+
+use std::borrow::Cow;
+
+use serde_json::Value;
+
+pub const MIN_WORD_LENGTH: usize = 4;
+pub const MAX_WORD_LENGTH: usize = 8;
+
+/// Where a word comes from: a language, a technology or an instruction set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Tag {
+    JavaScript,
+    Html,
+    Css,
+    Python,
+    Sql,
+    Bash,
+    Java,
+    CSharp,
+    C,
+    Cpp,
+    Php,
+    PowerShell,
+    Go,
+    Rust,
+    Kotlin,
+    Ruby,
+    Lua,
+    Dart,
+    Swift,
+    /// The name of a language, like "cobol".
+    Language,
+    /// Programming and computer words that don't belong to any one language.
+    General,
+    X86,
+    Arm64,
+    PowerPc,
+}
+
+impl Tag {
+    pub const ALL: [Tag; 24] = [
+        Tag::JavaScript,
+        Tag::Html,
+        Tag::Css,
+        Tag::Python,
+        Tag::Sql,
+        Tag::Bash,
+        Tag::Java,
+        Tag::CSharp,
+        Tag::C,
+        Tag::Cpp,
+        Tag::Php,
+        Tag::PowerShell,
+        Tag::Go,
+        Tag::Rust,
+        Tag::Kotlin,
+        Tag::Ruby,
+        Tag::Lua,
+        Tag::Dart,
+        Tag::Swift,
+        Tag::Language,
+        Tag::General,
+        Tag::X86,
+        Tag::Arm64,
+        Tag::PowerPc,
+    ];
+
+    /// What the tag is called in the dictionary files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Tag::JavaScript => "javascript",
+            Tag::Html => "html",
+            Tag::Css => "css",
+            Tag::Python => "python",
+            Tag::Sql => "sql",
+            Tag::Bash => "bash",
+            Tag::Java => "java",
+            Tag::CSharp => "csharp",
+            Tag::C => "c",
+            Tag::Cpp => "cpp",
+            Tag::Php => "php",
+            Tag::PowerShell => "powershell",
+            Tag::Go => "go",
+            Tag::Rust => "rust",
+            Tag::Kotlin => "kotlin",
+            Tag::Ruby => "ruby",
+            Tag::Lua => "lua",
+            Tag::Dart => "dart",
+            Tag::Swift => "swift",
+            Tag::Language => "language",
+            Tag::General => "general",
+            Tag::X86 => "x86",
+            Tag::Arm64 => "arm64",
+            Tag::PowerPc => "ppc",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tag::JavaScript => "JavaScript",
+            Tag::Html => "HTML",
+            Tag::Css => "CSS",
+            Tag::Python => "Python",
+            Tag::Sql => "SQL",
+            Tag::Bash => "Bash",
+            Tag::Java => "Java",
+            Tag::CSharp => "C#",
+            Tag::C => "C",
+            Tag::Cpp => "C++",
+            Tag::Php => "PHP",
+            Tag::PowerShell => "PowerShell",
+            Tag::Go => "Go",
+            Tag::Rust => "Rust",
+            Tag::Kotlin => "Kotlin",
+            Tag::Ruby => "Ruby",
+            Tag::Lua => "Lua",
+            Tag::Dart => "Dart",
+            Tag::Swift => "Swift",
+            Tag::Language => "Language name",
+            Tag::General => "General",
+            Tag::X86 => "x86",
+            Tag::Arm64 => "ARM64",
+            Tag::PowerPc => "PowerPC",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Tag> {
+        Tag::ALL.iter().copied().find(|t| t.id() == id)
+    }
+}
+
+/// One word from a dictionary file. The bundled ones borrow everything from
+/// the binary, anything loaded at runtime owns its strings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// Always lowercase once it's in a [`Dictionary`].
+    pub word: Cow<'static, str>,
+    pub tags: Cow<'static, [Tag]>,
+    /// What the word does, in a sentence or two.
+    pub description: Option<Cow<'static, str>>,
+    /// A few lines of code using it.
+    pub example: Option<Cow<'static, str>>,
+}
+
+impl Entry {
+    /// For the tables build.rs writes out.
+    pub(crate) const fn bundled(
+        word: &'static str,
+        tags: &'static [Tag],
+        description: Option<&'static str>,
+        example: Option<&'static str>,
+    ) -> Self {
+        Self {
+            word: Cow::Borrowed(word),
+            tags: Cow::Borrowed(tags),
+            description: match description {
+                Some(d) => Some(Cow::Borrowed(d)),
+                None => None,
+            },
+            example: match example {
+                Some(e) => Some(Cow::Borrowed(e)),
+                None => None,
+            },
+        }
+    }
+
+    /// Reads one entry of a dictionary file, as it's written.
+    pub fn parse(v: &Value) -> Result<Entry, String> {
+        if let Some(word) = v.as_str() {
+            return Ok(Entry {
+                word: word.to_string().into(),
+                tags: Cow::Borrowed(&[]),
+                description: None,
+                example: None,
+            });
+        }
+        let word = v
+            .get("word")
+            .and_then(|w| w.as_str())
+            .ok_or("missing \"word\"")?
+            .to_string();
+        let mut tags = Vec::new();
+        if let Some(t) = v.get("tags") {
+            for t in t.as_array().ok_or("\"tags\" should be a list")? {
+                let t = t.as_str().ok_or("tags should be strings")?;
+                tags.push(Tag::from_id(t).ok_or(format!("{}: unknown tag \"{}\"", word, t))?);
+            }
+        }
+        tags.sort();
+        tags.dedup();
+        let text = |key: &str| match v.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s.clone().into())),
+            Some(_) => Err(format!("{}: \"{}\" should be a string", word, key)),
+        };
+        Ok(Entry {
+            tags: tags.into(),
+            description: text("description")?,
+            example: text("example")?,
+            word: word.into(),
+        })
+    }
+
+    /// Whether the word is something we can put on the board: a plain word of
+    /// a length we play, in one case. Mixed case like `SETcc` stands for a
+    /// whole family of instructions.
+    pub fn is_playable(&self) -> bool {
+        (MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&self.word.len())
+            && (self.word.chars().all(|c| c.is_ascii_lowercase())
+                || self.word.chars().all(|c| c.is_ascii_uppercase()))
+    }
+
+    /// The entry the way it's written in a dictionary file, on one line. Just
+    /// the word if that's all there is to it.
+    pub fn to_json(&self) -> String {
+        let string = |s: &str| Value::from(s).to_string();
+        if self.tags.is_empty() && self.description.is_none() && self.example.is_none() {
+            return string(&self.word);
+        }
+        let tags: Vec<String> = self.tags.iter().map(|t| string(t.id())).collect();
+        let mut fields = vec![
+            format!("\"word\": {}", string(&self.word)),
+            format!("\"tags\": [{}]", tags.join(", ")),
+        ];
+        if let Some(description) = &self.description {
+            fields.push(format!("\"description\": {}", string(description)));
+        }
+        if let Some(example) = &self.example {
+            fields.push(format!("\"example\": {}", string(example)));
+        }
+        format!("{{{}}}", fields.join(", "))
+    }
+}
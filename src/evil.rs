@@ -0,0 +1,69 @@
+//! Evil mode, after Absurdle: there's no answer up front, just every word it
+//! could still be. Each guess gets whichever feedback keeps the most of them
+//! in play, so the answer only settles once there's nowhere left to go.
+
+use std::collections::BTreeMap;
+
+use crate::score::{self, Feedback};
+
+/// Scores `guess` the most unhelpful way it can be, returning the feedback
+/// and the candidates that are still left after it. Ties go to whichever
+/// feedback gives the least away.
+pub fn dodge(candidates: &[&'static str], guess: &str) -> (Vec<Feedback>, Vec<&'static str>) {
+    let mut buckets: BTreeMap<Vec<Feedback>, Vec<&'static str>> = BTreeMap::new();
+    for word in candidates {
+        buckets
+            .entry(score::score_guess(word, guess))
+            .or_default()
+            .push(word);
+    }
+    let revealed = |feedback: &[Feedback]| -> usize {
+        feedback
+            .iter()
+            .map(|f| match f {
+                Feedback::Absent => 0,
+                Feedback::Present => 1,
+                Feedback::Correct => 2,
+            })
+            .sum()
+    };
+    buckets
+        .into_iter()
+        .max_by(|(a, a_words), (b, b_words)| {
+            a_words
+                .len()
+                .cmp(&b_words.len())
+                .then(revealed(b).cmp(&revealed(a)))
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Feedback::*;
+
+    #[test]
+    fn keeps_the_biggest_group() {
+        let (feedback, left) = dodge(&["cloud", "alter", "while", "wharf"], "where");
+        assert_eq!(feedback, [Absent, Absent, Absent, Absent, Absent]);
+        assert_eq!(left, ["cloud"]);
+        let (feedback, left) = dodge(&["while", "whale", "wharf", "cloud"], "where");
+        assert_eq!(feedback, [Correct, Correct, Absent, Absent, Correct]);
+        assert_eq!(left, ["while", "whale"]);
+    }
+
+    #[test]
+    fn ties_give_the_least_away() {
+        let (feedback, left) = dodge(&["error", "cloud"], "array");
+        assert_eq!(feedback, [Absent, Absent, Absent, Absent, Absent]);
+        assert_eq!(left, ["cloud"]);
+    }
+
+    #[test]
+    fn only_the_answer_left() {
+        let (feedback, left) = dodge(&["error"], "error");
+        assert_eq!(feedback, [Correct; 5]);
+        assert_eq!(left, ["error"]);
+    }
+}
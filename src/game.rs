@@ -0,0 +1,942 @@
+//! The game itself, without any of the raylib stuff. The frontend turns
+//! keypresses and taps into [`Input`]s, feeds them to [`Game::handle`] and
+//! then draws whatever the `Game` looks like afterwards.
+
+use std::{collections::HashMap, time::Duration};
+
+use crate::{
+    dictionary::{self, Filter, WordList},
+    evil,
+    hint::{self, Hint, HINT_BUDGET},
+    rules::{self, HardModeViolation},
+    schedule::{self, Clock},
+    score::{self, Feedback},
+    solver::{self, Metric, RowReview, Suggestion},
+    speedrun::{self, Timer},
+};
+
+pub use crate::entry::{MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+pub const MIN_GUESS_LIMIT: usize = 4;
+pub const MAX_GUESS_LIMIT: usize = 10;
+pub const BOARD_COUNTS: [usize; 4] = [1, 2, 4, 8];
+pub const MAX_SEED: u64 = 1_000_000;
+/// Fewest answers a filter needs before it can be picked, so its daily word
+/// doesn't come round again every few days.
+pub const MIN_FILTER_ANSWERS: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Screen {
+    Title,
+    Game,
+    Won,
+    Lost,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Input {
+    Letter(char),
+    Backspace,
+    Submit,
+    Tap,
+    /// Asks for a [`Hint`].
+    Hint,
+}
+
+/// Why a guess or a hint wasn't accepted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Rejection {
+    /// The buffer doesn't have enough letters yet.
+    Incomplete,
+    /// The buffer isn't in the dictionary.
+    NotAWord,
+    /// Hard mode is on and the buffer ignores something we already know.
+    HardMode(HardModeViolation),
+    /// The puzzle's [`HINT_BUDGET`] is used up.
+    NoHints,
+    /// Every letter is already known.
+    NothingToHint,
+}
+
+impl std::fmt::Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rejection::Incomplete => write!(f, "Not enough letters"),
+            Rejection::NotAWord => write!(f, "Not in word list"),
+            Rejection::HardMode(v) => write!(f, "{}", v),
+            Rejection::NoHints => write!(f, "No hints left"),
+            Rejection::NothingToHint => write!(f, "Nothing left to hint"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// One puzzle per day.
+    #[default]
+    Daily,
+    /// Finishing a puzzle goes straight on to the next one.
+    Infinite,
+    /// Words picked from [`Settings::seed`] instead of the date, so there's
+    /// always another one.
+    Practice,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 3] = [Mode::Daily, Mode::Infinite, Mode::Practice];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Daily => "Daily",
+            Mode::Infinite => "Infinite",
+            Mode::Practice => "Practice",
+        }
+    }
+
+    pub fn next(&self) -> Mode {
+        let i = Mode::ALL.iter().position(|m| m == self).unwrap();
+        Mode::ALL[(i + 1) % Mode::ALL.len()]
+    }
+}
+
+/// Things that are picked on the title screen and stay fixed for the whole
+/// game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Settings {
+    pub mode: Mode,
+    /// Every guess has to reuse all the hints revealed so far.
+    pub hard_mode: bool,
+    /// Time each puzzle, see [`Game::timer`].
+    pub speedrun: bool,
+    /// The answer keeps changing to dodge guesses, see [`evil`]. Only ever
+    /// has one board.
+    pub evil: bool,
+    /// Use [`Settings::next_word_list`] to change this, so packs get their
+    /// word length.
+    pub word_list: WordList,
+    /// Only pick answers from some languages.
+    pub filter: Option<&'static Filter>,
+    /// Between [`MIN_WORD_LENGTH`] and [`MAX_WORD_LENGTH`].
+    pub word_length: usize,
+    /// How many rows the board has. Between [`MIN_GUESS_LIMIT`] and
+    /// [`MAX_GUESS_LIMIT`].
+    pub max_guesses: usize,
+    /// How many words have to be solved at once, one of [`BOARD_COUNTS`].
+    pub boards: usize,
+    /// Where practice mode gets its words from. Anyone playing the same seed
+    /// with the same settings gets the same words. Below [`MAX_SEED`] so it's
+    /// short enough to type in.
+    pub seed: u64,
+    /// When a new day (and a new daily word) starts.
+    pub clock: Clock,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mode: Mode::default(),
+            hard_mode: false,
+            speedrun: false,
+            evil: false,
+            word_list: WordList::default(),
+            filter: None,
+            word_length: 5,
+            max_guesses: 6,
+            boards: 1,
+            seed: 0,
+            clock: Clock::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Games with the same key share stats, and speedrun personal bests, so
+    /// an easy filter or extra guesses can't set the time for the real thing.
+    pub fn stats_key(&self) -> String {
+        let mut key = self.word_list.id().to_string();
+        if let Some(filter) = self.filter {
+            key += &format!("-{}", filter.id);
+        }
+        if self.word_length != 5 {
+            key += &format!("-{}", self.word_length);
+        }
+        if self.max_guesses != 6 {
+            key += &format!("-{}g", self.max_guesses);
+        }
+        if self.boards != 1 {
+            key += &format!("-{}x", self.boards);
+        }
+        if self.evil {
+            key += "-evil";
+        }
+        match self.mode {
+            Mode::Daily => {}
+            Mode::Infinite => key += "-infinite",
+            Mode::Practice => key += "-practice",
+        }
+        key
+    }
+
+    /// How many guesses the whole puzzle gets. Every extra board adds one
+    /// row, so eight boards with the default six rows get thirteen.
+    pub fn guess_limit(&self) -> usize {
+        self.max_guesses + self.boards - 1
+    }
+
+    pub fn new_seed(&mut self) {
+        self.seed = rand::random::<u64>() % MAX_SEED;
+    }
+
+    /// Adds a digit to the end of the seed, for typing one in.
+    pub fn push_seed_digit(&mut self, digit: u64) {
+        if self.seed * 10 + digit < MAX_SEED {
+            self.seed = self.seed * 10 + digit;
+        }
+    }
+
+    pub fn pop_seed_digit(&mut self) {
+        self.seed /= 10;
+    }
+
+    /// Every answer these settings can pick from, or every answer of the
+    /// right length if the filter doesn't have enough.
+    pub fn answers(&self) -> Vec<&'static str> {
+        match self.filter_fits() {
+            true => self.word_list.answers(self.word_length, self.filter),
+            false => self.word_list.answers(self.word_length, None),
+        }
+    }
+
+    /// How many answers there are to pick from with these settings.
+    pub fn answer_count(&self) -> usize {
+        self.word_list.answers(self.word_length, self.filter).len()
+    }
+
+    /// Whether the filter leaves enough answers to play with: at least
+    /// [`MIN_FILTER_ANSWERS`], and a different one for every board.
+    fn filter_fits(&self) -> bool {
+        self.answer_count() >= MIN_FILTER_ANSWERS.max(self.boards)
+    }
+
+    /// Moves on to the next word list. Packs come with their own word
+    /// length.
+    pub fn next_word_list(&mut self) {
+        self.word_list = self.word_list.next();
+        if let Some(length) = self.word_list.word_length() {
+            self.word_length = length;
+        }
+        self.check_filter();
+    }
+
+    /// Moves on to the next filter that has enough answers for this word
+    /// list, length and number of boards.
+    pub fn next_filter(&mut self) {
+        loop {
+            self.filter = Filter::next(self.filter);
+            if self.filter.is_none() || self.filter_fits() {
+                break;
+            }
+        }
+    }
+
+    /// Drops the filter if it doesn't leave enough to pick from, e.g. after
+    /// switching to a length it has hardly any words for.
+    pub fn check_filter(&mut self) {
+        if self.filter.is_some() && !self.filter_fits() {
+            self.filter = None;
+        }
+    }
+
+    /// Practice only, and one board only. Evil mode always starts from the
+    /// same candidates and dodges the same way, so a daily or infinite run
+    /// would just be the same puzzle over and over.
+    pub fn toggle_evil(&mut self) {
+        self.evil = !self.evil;
+        if self.evil {
+            self.boards = 1;
+            if self.mode != Mode::Practice {
+                self.mode = Mode::Practice;
+                self.new_seed();
+            }
+        }
+    }
+
+    /// Practice games get a fresh seed. Leaving practice turns evil mode off.
+    pub fn next_mode(&mut self) {
+        self.mode = self.mode.next();
+        match self.mode {
+            Mode::Practice => self.new_seed(),
+            _ => self.evil = false,
+        }
+    }
+
+    /// Does nothing in evil mode, which only has the one board.
+    pub fn next_boards(&mut self) {
+        if self.evil {
+            return;
+        }
+        let i = BOARD_COUNTS
+            .iter()
+            .position(|n| *n == self.boards)
+            .unwrap_or(0);
+        self.boards = BOARD_COUNTS[(i + 1) % BOARD_COUNTS.len()];
+        self.check_filter();
+    }
+
+    /// Does nothing for packs, which only have the one length.
+    pub fn next_word_length(&mut self) {
+        if self.word_list.word_length().is_some() {
+            return;
+        }
+        self.word_length = if self.word_length >= MAX_WORD_LENGTH {
+            MIN_WORD_LENGTH
+        } else {
+            self.word_length + 1
+        };
+        self.check_filter();
+    }
+
+    pub fn next_max_guesses(&mut self) {
+        self.max_guesses = if self.max_guesses >= MAX_GUESS_LIMIT {
+            MIN_GUESS_LIMIT
+        } else {
+            self.max_guesses + 1
+        };
+    }
+}
+
+/// A submitted guess and how it scored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Row {
+    pub word: String,
+    pub feedback: Vec<Feedback>,
+}
+
+/// One answer and the guesses scored against it. A board stops taking
+/// guesses once it's solved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    /// In evil mode this is just one of the candidates left, until there's
+    /// only one.
+    pub answer: String,
+    pub rows: Vec<Row>,
+    /// Every word the answer could still be in evil mode, empty otherwise.
+    pub candidates: Vec<&'static str>,
+    /// How many candidates there were at the start and after each guess, in
+    /// evil mode.
+    pub remaining: Vec<usize>,
+}
+
+impl Board {
+    pub fn new(answer: String) -> Self {
+        Self {
+            answer: answer.to_uppercase(),
+            rows: Vec::new(),
+            candidates: Vec::new(),
+            remaining: Vec::new(),
+        }
+    }
+
+    /// A board for evil mode, which could be any of `candidates`.
+    pub fn evil(candidates: Vec<&'static str>) -> Self {
+        Self {
+            answer: candidates[0].to_uppercase(),
+            rows: Vec::new(),
+            remaining: vec![candidates.len()],
+            candidates,
+        }
+    }
+
+    /// Scores a guess, first moving the answer out of the way in evil mode.
+    fn guess(&mut self, word: &str) {
+        let feedback = if self.candidates.is_empty() {
+            score::score_guess(&self.answer, word)
+        } else {
+            let (feedback, left) = evil::dodge(&self.candidates, word);
+            self.answer = left[0].to_uppercase();
+            self.remaining.push(left.len());
+            self.candidates = left;
+            feedback
+        };
+        self.rows.push(Row {
+            word: word.to_string(),
+            feedback,
+        });
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.rows
+            .last()
+            .is_some_and(|r| score::is_solved(&r.feedback))
+    }
+}
+
+/// A puzzle that's been won or lost.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finished {
+    pub boards: Vec<Board>,
+    /// How many guesses were submitted in total.
+    pub guesses: usize,
+    pub won: bool,
+    /// See [`Game::day`].
+    pub day: u64,
+    /// How many hints were used.
+    pub hints: usize,
+    /// How long it took, in speedrun mode.
+    pub time: Option<Duration>,
+}
+
+/// Running totals for everything played since the game was started.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Session {
+    pub solved: u32,
+    pub failed: u32,
+    pub streak: u32,
+    pub best_streak: u32,
+    /// Unused rows summed up over every solved puzzle, plus one per solve.
+    pub score: u32,
+}
+
+impl Session {
+    fn record(&mut self, finished: &Finished, guess_limit: usize) {
+        if finished.won {
+            self.solved += 1;
+            self.streak += 1;
+            self.best_streak = self.best_streak.max(self.streak);
+            self.score += (guess_limit - finished.guesses) as u32 + 1;
+        } else {
+            self.failed += 1;
+            self.streak = 0;
+        }
+    }
+}
+
+pub struct Game {
+    /// One per answer, see [`Settings::boards`].
+    pub boards: Vec<Board>,
+    /// How many guesses have been submitted for the current puzzle.
+    pub guesses: usize,
+    /// Which puzzle this is, counting from 1.
+    pub puzzle: i64,
+    /// The day the words were picked for, see [`Clock::today`].
+    pub day: u64,
+    pub buffer: String,
+    pub screen: Screen,
+    pub settings: Settings,
+    /// Why the last submission was refused, until the player types again.
+    pub rejection: Option<Rejection>,
+    /// Hints given for the current puzzle, oldest first.
+    pub hints: Vec<Hint>,
+    /// Times the current puzzle when [`Settings::speedrun`] is on. Starts on
+    /// the first keystroke, not when the board shows up.
+    pub timer: Timer,
+    /// Every puzzle that's been won or lost so far, oldest first.
+    pub finished: Vec<Finished>,
+    pub session: Session,
+    words: HashMap<i64, String>,
+}
+
+impl Game {
+    /// Starts on the title screen with today's words for these settings.
+    pub fn new(settings: Settings) -> Self {
+        let mut game = Self::with_answers(Vec::new(), settings);
+        game.boards = game.pick_boards();
+        game
+    }
+
+    /// Same as [`Game::new`] but with specific answers for the first puzzle.
+    pub fn with_answers(answers: Vec<String>, settings: Settings) -> Self {
+        Self {
+            boards: answers.into_iter().map(Board::new).collect(),
+            guesses: 0,
+            puzzle: 1,
+            day: settings.clock.today(),
+            buffer: String::new(),
+            screen: Screen::Title,
+            settings,
+            rejection: None,
+            hints: Vec::new(),
+            timer: Timer::default(),
+            finished: Vec::new(),
+            session: Session::default(),
+            words: HashMap::new(),
+        }
+    }
+
+    /// Gets the answers for the current puzzle. Every board of every puzzle
+    /// gets its own index for [`dictionary::get_word`], so puzzle 1 with four
+    /// boards uses 1 to 4, puzzle 2 uses 5 to 8 and so on.
+    fn pick_boards(&mut self) -> Vec<Board> {
+        if self.settings.evil {
+            return vec![Board::evil(self.settings.answers())];
+        }
+        let n = self.settings.boards as i64;
+        (0..n)
+            .map(|i| {
+                let k = (self.puzzle - 1) * n + i + 1;
+                Board::new(dictionary::get_word(
+                    k,
+                    self.day,
+                    &self.settings,
+                    &mut self.words,
+                ))
+            })
+            .collect()
+    }
+
+    pub fn handle(&mut self, input: Input) -> Result<(), Rejection> {
+        let result = self.apply(input);
+        match &result {
+            Err(r) => self.rejection = Some(r.clone()),
+            Ok(()) if input != Input::Tap => self.rejection = None,
+            Ok(()) => {}
+        }
+        result
+    }
+
+    fn apply(&mut self, input: Input) -> Result<(), Rejection> {
+        if self.settings.speedrun && self.screen == Screen::Game && input != Input::Tap {
+            self.timer.start();
+        }
+        match (self.screen, input) {
+            (Screen::Title, Input::Tap) => self.screen = Screen::Game,
+            (Screen::Game, Input::Letter(ch))
+                if self.buffer.len() < self.settings.word_length && ch.is_ascii_alphabetic() =>
+            {
+                self.buffer.push(ch.to_ascii_uppercase());
+            }
+            (Screen::Game, Input::Backspace) => {
+                self.buffer.pop();
+            }
+            (Screen::Game, Input::Submit) => self.submit()?,
+            (Screen::Game, Input::Hint) => self.hint()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn submit(&mut self) -> Result<(), Rejection> {
+        if self.is_out_of_guesses() {
+            return Ok(());
+        }
+        if self.buffer.len() < self.settings.word_length {
+            return Err(Rejection::Incomplete);
+        }
+        if !self.settings.word_list.is_valid(&self.buffer) {
+            return Err(Rejection::NotAWord);
+        }
+        if self.settings.hard_mode {
+            for board in self.boards.iter().filter(|b| !b.is_solved()) {
+                rules::check_hard_mode(&board.rows, &self.buffer).map_err(Rejection::HardMode)?;
+            }
+        }
+
+        let word = std::mem::take(&mut self.buffer);
+        for board in self.boards.iter_mut().filter(|b| !b.is_solved()) {
+            board.guess(&word);
+        }
+        self.guesses += 1;
+        if self.settings.speedrun {
+            self.timer.split();
+        }
+
+        let won = self.boards.iter().all(|b| b.is_solved());
+        if won || self.is_out_of_guesses() {
+            self.finish(won);
+        }
+        Ok(())
+    }
+
+    fn hint(&mut self) -> Result<(), Rejection> {
+        if self.hints_left() == 0 {
+            return Err(Rejection::NoHints);
+        }
+        let known = self.letter_states();
+        let hint = hint::next(&self.boards, &self.hints, &known, &mut rand::thread_rng())
+            .ok_or(Rejection::NothingToHint)?;
+        self.hints.push(hint);
+        Ok(())
+    }
+
+    /// Every word a board's answer could still be, going by its rows.
+    pub fn candidates(&self, board: &Board) -> Vec<&'static str> {
+        match board.candidates.is_empty() {
+            true => solver::candidates(&self.settings.answers(), &board.rows),
+            false => board.candidates.clone(),
+        }
+    }
+
+    /// The solver's picks for the next guess, best first, taking every
+    /// unsolved board into account. Sticks to hard mode if it's on.
+    pub fn suggest(&self, by: Metric) -> Vec<Suggestion> {
+        let unsolved: Vec<&Board> = self.boards.iter().filter(|b| !b.is_solved()).collect();
+        let candidates: Vec<Vec<&'static str>> =
+            unsolved.iter().map(|b| self.candidates(b)).collect();
+        let rows: Vec<&[Row]> = unsolved.iter().map(|b| b.rows.as_slice()).collect();
+        solver::rank(
+            &self.settings.word_list.guesses(self.settings.word_length),
+            &candidates,
+            &rows,
+            self.settings.hard_mode,
+            by,
+        )
+    }
+
+    /// How each row of every board went, see [`solver::review`].
+    pub fn reviews(&self) -> Vec<Vec<RowReview>> {
+        let rows: Vec<&[Row]> = self.boards.iter().map(|b| &b.rows[..]).collect();
+        solver::review(
+            &self.settings.word_list.guesses(self.settings.word_length),
+            &self.settings.answers(),
+            &rows,
+            self.settings.hard_mode,
+        )
+    }
+
+    /// The review as it's copied to paste into chat: the title, then a line
+    /// per row for each board.
+    pub fn review_text(&self, reviews: &[Vec<RowReview>]) -> String {
+        let mut text = format!("{} {}", self.title(), self.result());
+        for (board, rows) in self.boards.iter().zip(reviews) {
+            let (skill, luck) = solver::summary(rows);
+            text += &format!("\n{}: skill {}, luck {}", board.answer, skill, luck);
+            for row in rows {
+                text += &format!("\n{}", row);
+            }
+        }
+        text
+    }
+
+    /// There aren't any in evil mode, since there's no answer to give away
+    /// yet.
+    pub fn hints_left(&self) -> usize {
+        match self.settings.evil {
+            true => 0,
+            false => HINT_BUDGET - self.hints.len(),
+        }
+    }
+
+    /// The letters hints have given away for a board, by position.
+    pub fn hinted_letters(&self, board: usize) -> Vec<(usize, char)> {
+        self.hints
+            .iter()
+            .filter_map(|h| match h {
+                Hint::Letter {
+                    board: b,
+                    position,
+                    letter,
+                } if *b == board => Some((*position, *letter)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// A hint the way it's shown during the game. With more than one board
+    /// it says which word a hint is about.
+    pub fn describe_hint(&self, hint: &Hint) -> String {
+        match hint {
+            Hint::Tags { board, .. } | Hint::Letter { board, .. } if self.settings.boards > 1 => {
+                format!("Word {}: {}", board + 1, hint)
+            }
+            _ => hint.to_string(),
+        }
+    }
+
+    fn finish(&mut self, won: bool) {
+        self.timer.stop();
+        let finished = Finished {
+            boards: self.boards.clone(),
+            guesses: self.guesses,
+            won,
+            day: self.day,
+            hints: self.hints.len(),
+            time: self.settings.speedrun.then(|| self.timer.elapsed()),
+        };
+        self.session.record(&finished, self.settings.guess_limit());
+        self.finished.push(finished);
+
+        match self.settings.mode {
+            Mode::Daily | Mode::Practice => {
+                self.screen = if won { Screen::Won } else { Screen::Lost }
+            }
+            Mode::Infinite => self.next_puzzle(),
+        }
+    }
+
+    fn next_puzzle(&mut self) {
+        self.puzzle += 1;
+        self.boards = self.pick_boards();
+        self.guesses = 0;
+        self.buffer.clear();
+        self.hints.clear();
+        self.timer = Timer::default();
+    }
+
+    /// Whether the buffer should be drawn as an invalid word, i.e. it's full
+    /// but wouldn't be accepted.
+    pub fn buffer_is_invalid(&self) -> bool {
+        self.buffer.len() >= self.settings.word_length
+            && !self.settings.word_list.is_valid(&self.buffer)
+    }
+
+    pub fn is_out_of_guesses(&self) -> bool {
+        self.guesses >= self.settings.guess_limit()
+    }
+
+    /// Whether the game has been won or lost.
+    pub fn is_over(&self) -> bool {
+        matches!(self.screen, Screen::Won | Screen::Lost)
+    }
+
+    /// Whether the clock has moved on to a day with a different daily word
+    /// than the one being played.
+    pub fn is_stale(&self) -> bool {
+        self.settings.mode == Mode::Daily && self.settings.clock.today() != self.day
+    }
+
+    /// "Codle #N" for the daily puzzle, just "Codle" for anything else.
+    pub fn title(&self) -> String {
+        match self.settings.mode {
+            Mode::Daily => format!("Codle #{}", schedule::puzzle_number(self.day)),
+            _ => "Codle".to_string(),
+        }
+    }
+
+    /// The result as it's shown on the end screen, e.g. `3/6` or `X/6`.
+    pub fn result(&self) -> String {
+        match self.screen {
+            Screen::Lost => format!("X/{}", self.settings.guess_limit()),
+            _ => format!("{}/{}", self.guesses, self.settings.guess_limit()),
+        }
+    }
+
+    /// The spoiler-free result people paste into chat. With more than one
+    /// board it's just how many guesses each board took, since the grids
+    /// would get far too long.
+    pub fn share_text(&self) -> String {
+        let mut text = format!("{} {}", self.title(), self.result());
+        if self.settings.hard_mode {
+            text.push('*');
+        }
+        if !self.hints.is_empty() {
+            text += &format!(" 💡{}", self.hints.len());
+        }
+        if self.settings.speedrun && self.screen == Screen::Won {
+            text += &format!(" ⏱{}", speedrun::format_time(self.timer.elapsed()));
+        }
+        if let [board] = self.boards.as_slice() {
+            for row in &board.rows {
+                text.push('\n');
+                for f in &row.feedback {
+                    text.push(match f {
+                        Feedback::Correct => '🟩',
+                        Feedback::Present => '🟨',
+                        Feedback::Absent => '⬛',
+                    });
+                }
+            }
+        } else {
+            let results: Vec<String> = self
+                .boards
+                .iter()
+                .map(|b| match b.is_solved() {
+                    true => b.rows.len().to_string(),
+                    false => "X".to_string(),
+                })
+                .collect();
+            text.push('\n');
+            text += &results.join(" ");
+        }
+        text
+    }
+
+    /// See [`score::letter_states`]. Solved boards are left out, so the
+    /// keyboard only shows what's still useful. Letters from hints count
+    /// too.
+    pub fn letter_states(&self) -> HashMap<char, Feedback> {
+        let mut states = score::letter_states(
+            self.boards
+                .iter()
+                .filter(|b| !b.is_solved())
+                .flat_map(|b| b.rows.iter())
+                .map(|r| (r.word.as_str(), r.feedback.as_slice())),
+        );
+        for hint in &self.hints {
+            match hint {
+                Hint::Absent(letters) => {
+                    for c in letters {
+                        states.entry(*c).or_insert(Feedback::Absent);
+                    }
+                }
+                Hint::Letter { board, letter, .. } if !self.boards[*board].is_solved() => {
+                    states.insert(*letter, Feedback::Correct);
+                }
+                _ => {}
+            }
+        }
+        states
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start(answer: &str) -> Game {
+        let mut game = Game::with_answers(vec![answer.to_string()], Settings::default());
+        game.handle(Input::Tap).unwrap();
+        game
+    }
+
+    fn guess(game: &mut Game, word: &str) -> Result<(), Rejection> {
+        for ch in word.chars() {
+            game.handle(Input::Letter(ch)).unwrap();
+        }
+        game.handle(Input::Submit)
+    }
+
+    #[test]
+    fn win() {
+        let mut game = start("error");
+        assert_eq!(game.screen, Screen::Game);
+        guess(&mut game, "array").unwrap();
+        guess(&mut game, "error").unwrap();
+        assert_eq!(game.screen, Screen::Won);
+        assert_eq!(game.result(), "2/6");
+        assert!(game.finished[0].won);
+    }
+
+    #[test]
+    fn loss() {
+        let mut game = start("error");
+        for _ in 0..6 {
+            guess(&mut game, "array").unwrap();
+        }
+        assert_eq!(game.screen, Screen::Lost);
+        assert_eq!(game.result(), "X/6");
+        assert!(!game.finished[0].won);
+        // nothing else is taken once it's over
+        assert_eq!(guess(&mut game, "error"), Ok(()));
+        assert_eq!(game.guesses, 6);
+    }
+
+    #[test]
+    fn rejections() {
+        let mut game = start("error");
+        assert_eq!(guess(&mut game, "arr"), Err(Rejection::Incomplete));
+        assert_eq!(game.rejection, Some(Rejection::Incomplete));
+        game.handle(Input::Backspace).unwrap();
+        assert_eq!(game.rejection, None);
+        game.buffer.clear();
+        assert_eq!(guess(&mut game, "xqzvk"), Err(Rejection::NotAWord));
+        assert_eq!(game.guesses, 0);
+        assert_eq!(game.buffer, "XQZVK");
+    }
+
+    #[test]
+    fn hard_mode() {
+        let mut game = start("error");
+        game.settings.hard_mode = true;
+        guess(&mut game, "where").unwrap();
+        assert_eq!(
+            guess(&mut game, "cloud"),
+            Err(Rejection::HardMode(HardModeViolation::MissingLetter {
+                letter: 'E',
+                count: 1
+            }))
+        );
+        game.buffer.clear();
+        guess(&mut game, "alter").unwrap();
+        game.buffer.clear();
+        assert_eq!(
+            guess(&mut game, "where"),
+            Err(Rejection::HardMode(HardModeViolation::MissingGreen {
+                position: 4,
+                letter: 'R'
+            }))
+        );
+        assert_eq!(game.rejection.unwrap().to_string(), "5th letter must be R");
+        assert_eq!(game.guesses, 2);
+    }
+
+    #[test]
+    fn buffer_stops_at_word_length() {
+        let mut game = start("error");
+        for ch in "arrays".chars() {
+            game.handle(Input::Letter(ch)).unwrap();
+        }
+        assert_eq!(game.buffer, "ARRAY");
+    }
+
+    #[test]
+    fn filters_need_enough_answers() {
+        let css = dictionary::FILTERS.iter().find(|f| f.id == "css").unwrap();
+        let mut settings = Settings {
+            word_length: 7,
+            filter: Some(css),
+            ..Default::default()
+        };
+        assert!(settings.answer_count() < MIN_FILTER_ANSWERS);
+        assert!(settings.answers().len() > MIN_FILTER_ANSWERS);
+        settings.check_filter();
+        assert_eq!(settings.filter, None);
+
+        settings.boards = 8;
+        loop {
+            settings.next_filter();
+            match settings.filter {
+                Some(_) => assert!(settings.answer_count() >= MIN_FILTER_ANSWERS),
+                None => break,
+            }
+        }
+    }
+
+    #[test]
+    fn evil_is_practice_only() {
+        let mut settings = Settings {
+            boards: 4,
+            ..Default::default()
+        };
+        settings.toggle_evil();
+        assert_eq!((settings.mode, settings.boards), (Mode::Practice, 1));
+        settings.next_mode();
+        assert_ne!(settings.mode, Mode::Practice);
+        assert!(!settings.evil);
+    }
+
+    #[test]
+    fn hints_run_out() {
+        let mut game = start("yield");
+        for _ in 0..HINT_BUDGET {
+            game.handle(Input::Hint).unwrap();
+        }
+        assert!(matches!(game.hints[0], Hint::Tags { .. }));
+        assert!(matches!(game.hints[1], Hint::Absent(_)));
+        assert_eq!(game.handle(Input::Hint), Err(Rejection::NoHints));
+        assert_eq!(game.hints.len(), HINT_BUDGET);
+    }
+
+    #[test]
+    fn speedrun_timer() {
+        let settings = Settings {
+            speedrun: true,
+            ..Default::default()
+        };
+        let mut game = Game::with_answers(vec!["error".into()], settings);
+        game.handle(Input::Tap).unwrap();
+        assert!(!game.timer.is_running());
+        game.handle(Input::Letter('a')).unwrap();
+        assert!(game.timer.is_running());
+        game.handle(Input::Backspace).unwrap();
+        guess(&mut game, "array").unwrap();
+        assert_eq!(game.timer.splits.len(), 1);
+        // a rejected row isn't a split
+        assert!(guess(&mut game, "xqzvk").is_err());
+        assert_eq!(game.timer.splits.len(), 1);
+        game.buffer.clear();
+        guess(&mut game, "error").unwrap();
+        assert_eq!(game.timer.splits.len(), 2);
+        assert!(!game.timer.is_running());
+        assert_eq!(game.finished[0].time, Some(game.timer.elapsed()));
+        assert!(game.timer.splits[0] <= game.timer.splits[1]);
+    }
+}
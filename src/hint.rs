@@ -0,0 +1,214 @@
+//! Hints, for when you're stuck. Each puzzle gets [`HINT_BUDGET`] of them,
+//! and using any at all gets the result marked as hinted.
+
+use std::{collections::HashMap, fmt};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    dictionary::{self, Tag},
+    game::Board,
+    rules,
+    score::Feedback,
+};
+
+pub const HINT_BUDGET: usize = 3;
+/// How many absent letters get crossed off the keyboard at once.
+const ABSENT_BATCH: usize = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Hint {
+    /// What the answer on a board is used in.
+    Tags { board: usize, tags: Vec<Tag> },
+    /// Letters that aren't in any of the answers still being played.
+    Absent(Vec<char>),
+    /// One letter of an answer, in its place.
+    Letter {
+        board: usize,
+        position: usize,
+        letter: char,
+    },
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hint::Tags { tags, .. } => {
+                let names: Vec<&str> = tags.iter().map(|t| t.name()).collect();
+                write!(f, "Used in {}", names.join(", "))
+            }
+            Hint::Absent(letters) => {
+                let letters: Vec<String> = letters.iter().map(|c| c.to_string()).collect();
+                write!(f, "No {}", letters.join(", "))
+            }
+            Hint::Letter {
+                position, letter, ..
+            } => write!(f, "{} letter is {}", rules::ordinal(position + 1), letter),
+        }
+    }
+}
+
+/// The gentlest hint that still says something new: first what the answer
+/// is used in, then some letters it doesn't have, then its letters one at a
+/// time.
+/// `known` is what the keyboard already shows. Only unsolved boards get
+/// hints, the first of them before the rest. `rng` picks which letters.
+pub fn next(
+    boards: &[Board],
+    given: &[Hint],
+    known: &HashMap<char, Feedback>,
+    rng: &mut impl Rng,
+) -> Option<Hint> {
+    let unsolved: Vec<usize> = (0..boards.len())
+        .filter(|i| !boards[*i].is_solved())
+        .collect();
+    let &first = unsolved.first()?;
+
+    let tagged = given
+        .iter()
+        .any(|h| matches!(h, Hint::Tags { board, .. } if *board == first));
+    if !tagged {
+        let mut tags: Vec<Tag> = dictionary::lookup(&boards[first].answer)
+            .iter()
+            .flat_map(|e| e.tags.iter().copied())
+            .filter(|t| *t != Tag::General)
+            .collect();
+        tags.sort();
+        tags.dedup();
+        if !tags.is_empty() {
+            return Some(Hint::Tags { board: first, tags });
+        }
+    }
+
+    let crossed_off: Vec<char> = given
+        .iter()
+        .flat_map(|h| match h {
+            Hint::Absent(letters) => letters.clone(),
+            _ => Vec::new(),
+        })
+        .collect();
+    let absent: Vec<char> = ('A'..='Z')
+        .filter(|c| !unsolved.iter().any(|i| boards[*i].answer.contains(*c)))
+        .filter(|c| !known.contains_key(c) && !crossed_off.contains(c))
+        .collect();
+    let absent = (!absent.is_empty()).then(|| {
+        let mut letters: Vec<char> = absent.choose_multiple(rng, ABSENT_BATCH).copied().collect();
+        letters.sort();
+        Hint::Absent(letters)
+    });
+
+    let letter = unsolved.into_iter().find_map(|board| {
+        let answer: Vec<char> = boards[board].answer.chars().collect();
+        let hidden: Vec<usize> = (0..answer.len())
+            .filter(|p| {
+                !boards[board]
+                    .rows
+                    .iter()
+                    .any(|r| r.feedback.get(*p) == Some(&Feedback::Correct))
+            })
+            .filter(|p| {
+                !given.iter().any(|h| {
+                    matches!(h, Hint::Letter { board: b, position, .. } if *b == board && position == p)
+                })
+            })
+            .collect();
+        hidden.choose(rng).map(|&position| Hint::Letter {
+            board,
+            position,
+            letter: answer[position],
+        })
+    });
+
+    // one batch of absent letters, then letters until there's none left
+    match crossed_off.is_empty() {
+        true => absent.or(letter),
+        false => letter.or(absent),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn hint(boards: &[Board], given: &[Hint], known: &HashMap<char, Feedback>) -> Option<Hint> {
+        next(boards, given, known, &mut StdRng::seed_from_u64(1))
+    }
+
+    #[test]
+    fn tags_then_absent_letters_then_letters() {
+        let boards = [Board::new("yield".to_string())];
+        let known = HashMap::new();
+        let mut given = Vec::new();
+
+        let tags = hint(&boards, &given, &known).unwrap();
+        assert!(matches!(&tags, Hint::Tags { board: 0, tags } if tags.contains(&Tag::Python)));
+        given.push(tags);
+
+        let absent = hint(&boards, &given, &known).unwrap();
+        let Hint::Absent(letters) = &absent else {
+            panic!("expected absent letters, got {:?}", absent);
+        };
+        assert_eq!(letters.len(), ABSENT_BATCH);
+        assert!(letters.iter().all(|c| !"YIELD".contains(*c)));
+        given.push(absent);
+
+        for _ in 0..5 {
+            let letter = hint(&boards, &given, &known).unwrap();
+            let Hint::Letter {
+                board: 0,
+                position,
+                letter: l,
+            } = letter
+            else {
+                panic!("expected a letter, got {:?}", letter);
+            };
+            assert_eq!("YIELD".chars().nth(position), Some(l));
+            given.push(letter);
+        }
+        // every letter's given away, so it's back to crossing letters off
+        assert!(matches!(
+            hint(&boards, &given, &known),
+            Some(Hint::Absent(_))
+        ));
+    }
+
+    #[test]
+    fn skips_what_is_already_known() {
+        let boards = [Board::new("yield".to_string())];
+        let known: HashMap<char, Feedback> = "ABCDEFGHIJKLMNOPQR"
+            .chars()
+            .map(|c| (c, Feedback::Absent))
+            .collect();
+        let mut given = vec![
+            Hint::Tags {
+                board: 0,
+                tags: vec![Tag::Python],
+            },
+            Hint::Absent(vec!['S', 'T', 'U']),
+        ];
+        given.extend((0..5).map(|position| Hint::Letter {
+            board: 0,
+            position,
+            letter: "YIELD".chars().nth(position).unwrap(),
+        }));
+        // Y is in the answer, so only V, W, X and Z are left to cross off
+        assert_eq!(
+            hint(&boards, &given, &known),
+            Some(Hint::Absent(vec!['V', 'W', 'X', 'Z']))
+        );
+        given.push(Hint::Absent(vec!['V', 'W', 'X', 'Z']));
+        assert_eq!(hint(&boards, &given, &known), None);
+    }
+
+    #[test]
+    fn nothing_for_solved_boards() {
+        let mut board = Board::new("yield".to_string());
+        board.rows.push(crate::game::Row {
+            word: "YIELD".to_string(),
+            feedback: vec![Feedback::Correct; 5],
+        });
+        assert_eq!(hint(&[board], &[], &HashMap::new()), None);
+    }
+}
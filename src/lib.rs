@@ -0,0 +1,17 @@
+//! Everything that doesn't need raylib: the dictionaries, the game itself,
+//! scheduling, scoring and stats. The game (`main.rs`) draws all this, and
+//! `codle-dict` uses the dictionary side to look after the word lists.
+
+pub mod dictionary;
+mod entry;
+pub mod evil;
+pub mod game;
+pub mod hint;
+pub mod pack;
+pub mod rules;
+pub mod schedule;
+pub mod score;
+pub mod solver;
+pub mod speedrun;
+pub mod stats;
+mod table;
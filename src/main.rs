@@ -1,16 +1,21 @@
-use std::{collections::HashMap, time::SystemTime};
-
-use serde_json::Value;
-
-const MAX_GUESSES: usize = 5;
+use std::time::{Duration, SystemTime};
 
+use codle::{
+    dictionary::WordList,
+    game::{self, Input, Mode, Screen, Settings},
+    pack::{self, Pack},
+    schedule::{self, Clock},
+    score::Feedback,
+    solver, speedrun, stats,
+};
 use raylib::prelude::*;
-use state::{get_word, State, DICTIONARY};
+use state::State;
 
 mod models;
 mod state;
 mod utils;
-/* used:
+/* used (every word is tagged with which of these it came from, see
+   dictionary::Tag):
     - Javascript reserved keywords
     - HTML tags
     - CSS properties
@@ -35,6 +40,9 @@ mod utils;
     - x86 mnemonics
     - ARM64 mnemonics
     - PPC mnemonics
+
+    guesses.json has everything else we accept as a guess (plain English and
+    general programming words), none of which ever come up as the answer.
 */
 
 #[cfg(feature = "wasm")]
@@ -44,18 +52,67 @@ extern "C" {
     fn GetWindowInnerHeight() -> i32;
 }
 
-#[derive(PartialEq)]
-enum Screen {
-    Title,
-    Game,
-    Won,
-}
-
 const GRAY: Color = Color::new(63, 63, 70, 255);
 const BLUE: Color = Color::new(31, 41, 55, 255);
+const GREEN: Color = Color::new(0, 204, 136, 255);
+const YELLOW: Color = Color::new(255, 204, 0, 255);
+/// Reads `--seed N` off the command line, which starts straight into a
+/// practice game with that seed, `--utc-offset +HH:MM`, which picks when
+/// the daily word changes, and `--pack FILE`, which plays a word pack. Also
+/// loads the packs in [`pack::dir`] for the menu.
+#[cfg(not(feature = "wasm"))]
+fn settings_from_args() -> Settings {
+    let mut settings = Settings {
+        clock: stats::load_clock().unwrap_or_else(Clock::local),
+        ..Default::default()
+    };
+    pack::load_dir();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                if let Some(seed) = args.next().and_then(|s| s.parse::<u64>().ok()) {
+                    settings.mode = Mode::Practice;
+                    settings.seed = seed % game::MAX_SEED;
+                }
+            }
+            "--utc-offset" => match args.next().as_deref().and_then(Clock::parse) {
+                Some(clock) => settings.clock = clock,
+                None => eprintln!("--utc-offset wants something like +02:00"),
+            },
+            "--pack" => {
+                let Some(path) = args.next() else {
+                    eprintln!("--pack wants the path to a pack file");
+                    std::process::exit(1);
+                };
+                match Pack::load(path.as_ref()) {
+                    Ok(p) => {
+                        let p = pack::add(p);
+                        settings.word_list = WordList::Pack(p);
+                        settings.word_length = p.word_length;
+                    }
+                    Err(e) => {
+                        eprintln!("couldn't load {}: {}", path, e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    settings
+}
+
+#[cfg(feature = "wasm")]
+fn settings_from_args() -> Settings {
+    Settings {
+        clock: stats::load_clock().unwrap_or_else(Clock::local),
+        ..Default::default()
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut state = State::new()?;
-    let word_chars = state.word.chars().clone();
+    let mut state = State::new(settings_from_args())?;
 
     #[cfg(not(feature = "wasm"))]
     let width = get_monitor_width(get_current_monitor_index()) / 2;
@@ -68,75 +125,387 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         #[cfg(feature = "wasm")]
         let height = unsafe { GetWindowInnerHeight() };
         state.rl.set_window_size(width, height);
+        let screen_before = state.game.screen;
 
         let screen_width = state.rl.get_screen_width();
         let screen_height = state.rl.get_screen_height();
 
-        let valid = DICTIONARY.contains(&Value::String(
-            state
-                .buffer
-                .iter()
-                .map(|f| utils::get_letter(f))
-                .collect::<Vec<&str>>()
-                .join("")
-                .to_string()
-                .to_lowercase(),
-        ));
         let f_width = state.rl.get_screen_width() as f32 / 10.0;
-        match state.screen {
+        let mut new_day = false;
+        let mut next_card = false;
+        match state.game.screen {
             Screen::Title => {
-                let mut d = state.rl.begin_drawing(&state.thread);
-                d.clear_background(BLUE);
-
-                utils::draw_text_centered(
-                    &mut d,
-                    &state.font,
-                    f_width,
-                    "CODLE",
-                    screen_width,
-                    32.0,
-                    1.0,
-                );
-
-                let f_width = f_width * 0.5;
-                utils::draw_text_centered(
-                    &mut d,
-                    &state.font,
-                    f_width,
-                    "Get 6 chances to guess a 5 letter",
-                    screen_width,
-                    (screen_height / 4) as f32,
-                    1.0,
-                );
-                utils::draw_text_centered(
-                    &mut d,
-                    &state.font,
-                    f_width,
-                    "word that's related to programming",
-                    screen_width,
-                    (screen_height / 4) as f32 + (f_width + 4.0),
-                    1.0,
-                );
-                utils::draw_text_centered(
-                    &mut d,
-                    &state.font,
-                    f_width,
-                    "Click anywhere to begin.",
-                    screen_width,
-                    (screen_height - (screen_height / 4)) as f32,
-                    1.0,
-                );
-
-                if d.is_gesture_detected(Gesture::GESTURE_TAP) {
-                    state.screen = Screen::Game;
+                // nothing's been played yet, so just swap in the new word
+                if state.game.is_stale() {
+                    state.restart();
+                }
+                let (
+                    next_mode,
+                    toggle_hard_mode,
+                    toggle_evil,
+                    toggle_speedrun,
+                    next_word_list,
+                    next_filter,
+                    next_word_length,
+                    next_max_guesses,
+                    next_boards,
+                    next_seed,
+                    next_rollover,
+                    begin,
+                ) = {
+                    let mut d = state.rl.begin_drawing(&state.thread);
+                    d.clear_background(BLUE);
+
+                    utils::draw_text_centered(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        &state.game.title().to_uppercase(),
+                        screen_width,
+                        32.0,
+                        1.0,
+                    );
+
+                    let f_width = f_width * 0.5;
+                    utils::draw_text_centered(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        &match state.game.settings.boards {
+                            1 => format!(
+                                "Get {} chances to guess a {} letter",
+                                state.game.settings.guess_limit(),
+                                state.game.settings.word_length
+                            ),
+                            n => format!(
+                                "Get {} chances to guess {} {} letter",
+                                state.game.settings.guess_limit(),
+                                n,
+                                state.game.settings.word_length
+                            ),
+                        },
+                        screen_width,
+                        (screen_height / 4) as f32,
+                        1.0,
+                    );
+                    utils::draw_text_centered(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        &match (state.game.settings.word_list, state.game.settings.boards) {
+                            (WordList::Pack(p), 1) => format!("word from {}", p.name),
+                            (WordList::Pack(p), _) => format!("words from {}", p.name),
+                            (_, 1) => "word that's related to programming".to_string(),
+                            _ => "words that are related to programming".to_string(),
+                        },
+                        screen_width,
+                        (screen_height / 4) as f32 + (f_width + 4.0),
+                        1.0,
+                    );
+                    let mode = utils::draw_option(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        &format!("Mode: {} (M)", state.game.settings.mode.name()),
+                        screen_width,
+                        (screen_height / 2) as f32 - (f_width + 4.0) * 3.0,
+                    );
+                    let hard_mode = utils::draw_option(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        &format!(
+                            "Hard mode: {} (H)",
+                            if state.game.settings.hard_mode {
+                                "on"
+                            } else {
+                                "off"
+                            }
+                        ),
+                        screen_width,
+                        (screen_height / 2) as f32 - (f_width + 4.0) * 2.0,
+                    );
+                    let evil = utils::draw_option(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        &format!(
+                            "Evil: {} (V)",
+                            if state.game.settings.evil {
+                                "on"
+                            } else {
+                                "off"
+                            }
+                        ),
+                        screen_width,
+                        (screen_height / 2) as f32 - (f_width + 4.0),
+                    );
+                    let speedrun = utils::draw_option(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        &match state.stats.best_time(&state.game.settings) {
+                            Some(best) if state.game.settings.speedrun => {
+                                format!("Speedrun: on, PB {} (P)", speedrun::format_time(best))
+                            }
+                            _ if state.game.settings.speedrun => "Speedrun: on (P)".to_string(),
+                            _ => "Speedrun: off (P)".to_string(),
+                        },
+                        screen_width,
+                        (screen_height / 2) as f32,
+                    );
+                    let word_list = utils::draw_option(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        &match state.game.settings.word_list {
+                            WordList::Pack(Pack {
+                                name,
+                                author: Some(author),
+                                ..
+                            }) => format!("Words: {} by {} (W)", name, author),
+                            list => format!("Words: {} (W)", list.name()),
+                        },
+                        screen_width,
+                        (screen_height / 2) as f32 + (f_width + 4.0),
+                    );
+                    let filter = utils::draw_option(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        &match state.game.settings.filter {
+                            Some(f) => format!(
+                                "Topic: {}, {} words (T)",
+                                f.name,
+                                state.game.settings.answer_count()
+                            ),
+                            None => "Topic: Everything (T)".to_string(),
+                        },
+                        screen_width,
+                        (screen_height / 2) as f32 + (f_width + 4.0) * 2.0,
+                    );
+                    let word_length = utils::draw_option(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        &match state.game.settings.word_list {
+                            WordList::Pack(_) => {
+                                format!(
+                                    "Length: {}, set by the pack",
+                                    state.game.settings.word_length
+                                )
+                            }
+                            _ => format!("Length: {} (L)", state.game.settings.word_length),
+                        },
+                        screen_width,
+                        (screen_height / 2) as f32 + (f_width + 4.0) * 3.0,
+                    );
+                    let max_guesses = utils::draw_option(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        &format!("Guesses: {} (G)", state.game.settings.guess_limit()),
+                        screen_width,
+                        (screen_height / 2) as f32 + (f_width + 4.0) * 4.0,
+                    );
+                    let boards = utils::draw_option(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        &match state.game.settings.evil {
+                            true => "Boards: 1, evil only has one".to_string(),
+                            false => format!("Boards: {} (B)", state.game.settings.boards),
+                        },
+                        screen_width,
+                        (screen_height / 2) as f32 + (f_width + 4.0) * 5.0,
+                    );
+                    let new_seed = state.game.settings.mode == Mode::Practice
+                        && utils::draw_option(
+                            &mut d,
+                            &state.font,
+                            f_width,
+                            &format!("Seed: {} (S for a new one)", state.game.settings.seed),
+                            screen_width,
+                            (screen_height / 2) as f32 + (f_width + 4.0) * 6.0,
+                        );
+                    let rollover = state.game.settings.mode == Mode::Daily
+                        && utils::draw_option(
+                            &mut d,
+                            &state.font,
+                            f_width,
+                            &format!(
+                                "New word at midnight {} (R)",
+                                state.game.settings.clock.name()
+                            ),
+                            screen_width,
+                            (screen_height / 2) as f32 + (f_width + 4.0) * 6.0,
+                        );
+                    utils::draw_text_centered(
+                        &mut d,
+                        &state.font,
+                        f_width,
+                        "Click anywhere to begin.",
+                        screen_width,
+                        (screen_height - (screen_height / 4)) as f32,
+                        1.0,
+                    );
+
+                    (
+                        mode || d.is_key_pressed(KeyboardKey::KEY_M),
+                        hard_mode || d.is_key_pressed(KeyboardKey::KEY_H),
+                        evil || d.is_key_pressed(KeyboardKey::KEY_V),
+                        speedrun || d.is_key_pressed(KeyboardKey::KEY_P),
+                        word_list || d.is_key_pressed(KeyboardKey::KEY_W),
+                        filter || d.is_key_pressed(KeyboardKey::KEY_T),
+                        word_length || d.is_key_pressed(KeyboardKey::KEY_L),
+                        max_guesses || d.is_key_pressed(KeyboardKey::KEY_G),
+                        boards || d.is_key_pressed(KeyboardKey::KEY_B),
+                        new_seed
+                            || (state.game.settings.mode == Mode::Practice
+                                && d.is_key_pressed(KeyboardKey::KEY_S)),
+                        rollover
+                            || (state.game.settings.mode == Mode::Daily
+                                && d.is_key_pressed(KeyboardKey::KEY_R)),
+                        d.is_gesture_detected(Gesture::GESTURE_TAP),
+                    )
+                };
+
+                // practice seeds can be typed in to replay someone else's word
+                if state.game.settings.mode == Mode::Practice {
+                    let seed = state.game.settings.seed;
+                    while let Some(c) = state.rl.get_char_pressed() {
+                        if let Some(digit) = c.to_digit(10) {
+                            state.game.settings.push_seed_digit(digit as u64);
+                        }
+                    }
+                    if state.rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+                        state.game.settings.pop_seed_digit();
+                    }
+                    if state.game.settings.seed != seed {
+                        state.restart();
+                    }
+                }
+
+                if next_mode {
+                    state.game.settings.next_mode();
+                    state.restart();
+                } else if toggle_hard_mode {
+                    state.game.settings.hard_mode = !state.game.settings.hard_mode;
+                } else if toggle_evil {
+                    state.game.settings.toggle_evil();
+                    state.restart();
+                } else if toggle_speedrun {
+                    state.game.settings.speedrun = !state.game.settings.speedrun;
+                } else if next_word_list {
+                    state.game.settings.next_word_list();
+                    state.restart();
+                } else if next_filter {
+                    state.game.settings.next_filter();
+                    state.restart();
+                } else if next_word_length {
+                    state.game.settings.next_word_length();
+                    state.restart();
+                } else if next_max_guesses {
+                    state.game.settings.next_max_guesses();
+                    state.restart();
+                } else if next_boards {
+                    state.game.settings.next_boards();
+                    state.restart();
+                } else if next_seed {
+                    state.game.settings.new_seed();
+                    state.restart();
+                } else if next_rollover {
+                    state.game.settings.clock = state.game.settings.clock.next_offset();
+                    if let Err(e) = stats::save_clock(state.game.settings.clock) {
+                        eprintln!("couldn't save the rollover: {}", e);
+                    }
+                    state.restart();
+                } else if begin {
+                    let _ = state.game.handle(Input::Tap);
                 }
             }
-            Screen::Won | Screen::Game => {
-                if let Some(k) = state.rl.get_key_pressed() {
-                    if state.screen == Screen::Game {
-                        utils::push_valid_word(&mut state.buffer, k);
+            Screen::Won | Screen::Lost | Screen::Game => {
+                while let Some(k) = state.rl.get_key_pressed() {
+                    if state.game.is_over() && k == KeyboardKey::KEY_C {
+                        let text = match state.review {
+                            Some(_) => {
+                                let reviews = state.reviews().to_vec();
+                                state.game.review_text(&reviews)
+                            }
+                            None => state.game.share_text(),
+                        };
+                        let _ = state.rl.set_clipboard_text(&text);
+                    } else if state.game.is_over() && k == KeyboardKey::KEY_E {
+                        state.next_card();
+                    } else if state.game.is_over() && k == KeyboardKey::KEY_R {
+                        state.next_review();
+                    } else if state.game.is_over()
+                        && state.game.settings.mode == Mode::Practice
+                        && k == KeyboardKey::KEY_N
+                    {
+                        state.game.settings.new_seed();
+                        state.restart();
+                        let _ = state.game.handle(Input::Tap);
+                    } else if state.game.is_over()
+                        && state.game.is_stale()
+                        && k == KeyboardKey::KEY_N
+                    {
+                        new_day = true;
+                    } else if state.game.screen == Screen::Lost && k == KeyboardKey::KEY_L {
+                        state.show_left = !state.show_left;
+                        state.card = None;
+                        state.review = None;
+                    } else if k == KeyboardKey::KEY_TAB {
+                        state.assist = !state.assist;
+                    } else if let Some(input) = utils::key_input(k) {
+                        let _ = state.game.handle(input);
                     }
                 }
+                // before anything's drawn, so the end screen fades in from
+                // the frame the game ended on
+                if state.game.screen != screen_before && state.game.is_over() {
+                    state.end_time = SystemTime::now();
+                }
+                state.follow_board();
+                let index = state.board_index();
+                // worked out before drawing starts, since it needs all of state
+                let candidates = state.candidates().to_vec();
+                let review = match state.review {
+                    Some(i) => state.reviews().get(i).cloned(),
+                    None => None,
+                };
+                let left: Vec<Option<usize>> = candidates
+                    .iter()
+                    .zip(&state.game.boards)
+                    .map(|(c, b)| (!b.is_solved()).then_some(c.len()))
+                    .collect();
+                // everything but the answer that was still possible
+                let missed: Vec<String> = match state.game.screen {
+                    Screen::Lost => candidates
+                        .iter()
+                        .zip(&state.game.boards)
+                        .filter(|(_, b)| !b.is_solved())
+                        .flat_map(|(c, b)| {
+                            c.iter()
+                                .map(|w| w.to_uppercase())
+                                .filter(move |w| *w != b.answer)
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                let assist = (state.assist && state.game.screen == Screen::Game).then(|| {
+                    let words: Vec<String> = state
+                        .suggestions()
+                        .iter()
+                        .take(3)
+                        .map(|s| s.word.to_uppercase())
+                        .collect();
+                    match words.as_slice() {
+                        [] => "Assist: nothing fits (Tab)".to_string(),
+                        [word] => format!("Try {} (Tab)", word),
+                        [rest @ .., last] => format!("Try {} or {} (Tab)", rest.join(", "), last),
+                    }
+                });
                 let mut d_ = state.rl.begin_drawing(&state.thread);
                 let mut offset = (state.camera.position.z as i64) / 10;
                 d_.clear_background(BLUE);
@@ -147,178 +516,213 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     if offset >= 100 {
                         offset = 100;
                     }
-                    let mut solved_num = 0;
-
-                    for guess in 0..=MAX_GUESSES {
-                        let mut yellow_letters_marked: HashMap<char, usize> = HashMap::new();
-                        for letter in 0..=4 {
-                            let pos = Vector3::new(
-                                16.0 - (letter as f32 * 16.0),
-                                16.0 - (guess as f32 * 16.0),
-                                (7.0) - (offset as f32),
-                            );
+                    let settings = &state.game.settings;
+                    let board_origin = |puzzle: usize, board: usize| {
+                        let mut origin = state::board_origin(settings, puzzle, board);
+                        origin.z = (7.0) - (offset as f32);
+                        origin
+                    };
 
-                            if let Some(g) = state.guessed.get(guess) {
-                                let g = g.clone();
-                                if let Some(ch) = g.chars().nth(letter) {
-                                    let arr = if g == state.word {
-                                        solved_num += 1;
-                                        &state.models.green_letters
-                                    } else {
-                                        if state.word.contains(ch) {
-                                            if let Some(word_char) = word_chars.clone().nth(letter)
-                                            {
-                                                if word_char == ch {
-                                                    solved_num += 1;
-                                                    &state.models.green_letters
-                                                } else {
-                                                    solved_num = 0;
-                                                    if !yellow_letters_marked.contains_key(&ch) {
-                                                        yellow_letters_marked.insert(ch, 1);
-                                                        &state.models.yellow_letters
-                                                    } else {
-                                                        let am =
-                                                            yellow_letters_marked.get(&ch).unwrap();
-
-                                                        let what = state
-                                                            .word
-                                                            .matches(ch)
-                                                            .collect::<Vec<&str>>()
-                                                            .len();
-                                                        if am < &what {
-                                                            &state.models.yellow_letters
-                                                        } else {
-                                                            &state.models.letters
-                                                        }
-                                                    }
-                                                }
-                                            } else {
-                                                solved_num = 0;
-                                                &state.models.letters
-                                            }
-                                        } else {
-                                            solved_num = 0;
-                                            &state.models.letters
-                                        }
-                                    };
-
-                                    if let Some(lette) = arr.get(ch as usize - 65) {
-                                        let l = match state.show_letters {
-                                            true => &lette.1,
-                                            false => &lette.0,
-                                        };
-                                        d.draw_model(l, pos, 1.0, Color::WHITE);
-                                    }
-                                }
-                            } else {
-                                if guess == state.guessed.len() {
-                                    if let Some(t) = &state.buffer.get(letter) {
-                                        if let Some(ch) = utils::get_letter(&t).chars().nth(0) {
-                                            let letters = if valid || state.buffer.len() < 5 {
-                                                &state.models.letters
-                                            } else {
-                                                &state.models.red_letters
-                                            };
-                                            if ch as usize >= 65 {
-                                                if let Some(lette) = letters.get(ch as usize - 65) {
-                                                    let l = match state.show_letters {
-                                                        true => &lette.1,
-                                                        false => &lette.0,
-                                                    };
-                                                    d.draw_model(l, pos, 1.0, Color::WHITE);
-                                                    solved_num = 0;
-                                                }
-                                            }
-                                        }
-                                    } else {
-                                        d.draw_model(
-                                            &state.cube,
-                                            pos,
-                                            1.0,
-                                            Color::new(24, 24, 27, 255),
-                                        )
-                                    }
-                                } else {
-                                    d.draw_model(&state.cube, pos, 1.0, Color::new(24, 24, 27, 255))
-                                }
-                            }
-                        }
-                        if solved_num >= 5 {
-                            if state.screen != Screen::Won {
-                                state.screen = Screen::Won;
-                                state.win_time = SystemTime::now();
+                    // in infinite mode the last puzzle stays put while the
+                    // camera moves over to the new one
+                    if index > 0 {
+                        if let Some(last) = state.game.finished.last() {
+                            for (i, board) in last.boards.iter().enumerate() {
+                                utils::draw_board(
+                                    &mut d,
+                                    &state.models,
+                                    &state.cube,
+                                    state.show_letters,
+                                    &utils::BoardView {
+                                        rows: &board.rows,
+                                        buffer: None,
+                                        answer: (!board.is_solved())
+                                            .then_some(board.answer.as_str()),
+                                        hints: &[],
+                                        word_length: settings.word_length,
+                                        max_guesses: settings.guess_limit(),
+                                    },
+                                    board_origin(index - 1, i),
+                                );
                             }
-                        } else {
-                            solved_num = 0;
                         }
                     }
-                    if state.guessed.len() >= (MAX_GUESSES + 1) {
-                        let mut n = 0;
-                        let get_word = &get_word(1 as i64, &mut state.words);
-
-                        for letter in get_word.split("") {
-                            if let Some(ch) = letter.chars().nth(0) {
-                                if let Some(lette) = state.models.letters.get(ch as usize - 65) {
-                                    d.draw_model(
-                                        &lette.1,
-                                        Vector3::new(
-                                            16.0 - (n as f32 * 16.0) + 16.0,
-                                            16.0 - (6.0 * 16.0),
-                                            (7.0) - (offset as f32),
-                                        ),
-                                        1.0,
-                                        Color::WHITE,
-                                    );
-                                }
-                            }
-                            n += 1;
-                        }
+
+                    let invalid = state.game.buffer_is_invalid();
+                    for (i, board) in state.game.boards.iter().enumerate() {
+                        utils::draw_board(
+                            &mut d,
+                            &state.models,
+                            &state.cube,
+                            state.show_letters,
+                            &utils::BoardView {
+                                rows: &board.rows,
+                                buffer: (!board.is_solved())
+                                    .then_some((state.game.buffer.as_str(), invalid)),
+                                answer: (state.game.screen == Screen::Lost && !board.is_solved())
+                                    .then_some(board.answer.as_str()),
+                                hints: &state.game.hinted_letters(i),
+                                word_length: settings.word_length,
+                                max_guesses: settings.guess_limit(),
+                            },
+                            board_origin(index, i),
+                        );
                     }
                 }
 
-                match state.screen {
-                    Screen::Game => {
-                        if d_.is_key_released(KeyboardKey::KEY_ENTER) {
-                            if state.buffer.len() == 5 && valid {
-                                let mut g = String::new();
-                                for key in &state.buffer {
-                                    g += utils::get_letter(key);
-                                }
-                                state.guessed.push(g.to_uppercase());
-                                state.buffer.truncate(0);
-                            }
-                        }
-                        if d_.is_key_released(KeyboardKey::KEY_BACKSPACE) {
-                            state.buffer.pop();
-                        }
+                if state.game.settings.mode == Mode::Practice {
+                    utils::draw_text_centered(
+                        &mut d_,
+                        &state.font,
+                        f_width * 0.4,
+                        &format!("Practice #{}", state.game.settings.seed),
+                        screen_width,
+                        8.0,
+                        1.0,
+                    );
+                } else if state.game.is_stale() {
+                    new_day |= utils::draw_option(
+                        &mut d_,
+                        &state.font,
+                        f_width * 0.4,
+                        &format!(
+                            "Codle #{} is out! Tap here to play it",
+                            schedule::puzzle_number(state.game.settings.clock.today())
+                        ),
+                        screen_width,
+                        8.0,
+                    );
+                } else if state.game.settings.mode == Mode::Infinite {
+                    let session = &state.game.session;
+                    utils::draw_text_centered(
+                        &mut d_,
+                        &state.font,
+                        f_width * 0.4,
+                        &format!(
+                            "Solved {}  Streak {}  Score {}",
+                            session.solved, session.streak, session.score
+                        ),
+                        screen_width,
+                        8.0,
+                        1.0,
+                    );
+                }
+
+                if state.game.settings.speedrun {
+                    let timer = &state.game.timer;
+                    let mut clock = speedrun::format_time(timer.elapsed());
+                    // how long the last row took
+                    if let Some(last) = timer.splits.last() {
+                        let before = match timer.splits.len() {
+                            1 => Duration::ZERO,
+                            n => timer.splits[n - 2],
+                        };
+                        clock += &format!("  +{}", speedrun::format_time(*last - before));
+                    }
+                    utils::draw_text_centered(
+                        &mut d_,
+                        &state.font,
+                        f_width * 0.4,
+                        &clock,
+                        screen_width,
+                        8.0 + f_width * 0.5,
+                        1.0,
+                    );
+                }
 
+                match state.game.screen {
+                    Screen::Game => {
                         let key_width = width / 10;
                         let key_height = height / 12;
                         let font_size = key_height as f32 * 0.75;
                         let mut y = height - (key_height * 3);
-                        let vec = &state.guessed.clone();
-                        let l = vec
+                        let letter_states = state.game.letter_states();
+
+                        if let Some(rejection) = &state.game.rejection {
+                            utils::draw_text_centered(
+                                &mut d_,
+                                &state.font,
+                                font_size * 0.75,
+                                &rejection.to_string(),
+                                screen_width,
+                                y as f32 - font_size * 1.5,
+                                1.0,
+                            );
+                        }
+
+                        if utils::draw_option(
+                            &mut d_,
+                            &state.font,
+                            f_width * 0.4,
+                            assist.as_deref().unwrap_or("Assist: off (Tab)"),
+                            screen_width,
+                            8.0 + f_width,
+                        ) {
+                            state.assist = !state.assist;
+                        }
+
+                        let counts: Vec<String> = left
                             .iter()
-                            .map(|f| f.split("").into_iter().collect::<Vec<&str>>())
-                            .flat_map(|f| f)
-                            .collect::<Vec<&str>>();
+                            .map(|n| n.map_or("-".to_string(), |n| n.to_string()))
+                            .collect();
+                        utils::draw_text_centered(
+                            &mut d_,
+                            &state.font,
+                            f_width * 0.4,
+                            &match left.as_slice() {
+                                [Some(1)] => "1 word left".to_string(),
+                                [Some(n)] => format!("{} words left", n),
+                                _ => format!("Words left: {}", counts.join("  ")),
+                            },
+                            screen_width,
+                            8.0 + f_width * 1.5,
+                            1.0,
+                        );
+
+                        let hint_y = y as f32 - font_size * 3.0;
+                        let ask_hint = utils::draw_option(
+                            &mut d_,
+                            &state.font,
+                            font_size * 0.75,
+                            &match state.game.hints_left() {
+                                0 if state.game.settings.evil => {
+                                    "No hints in evil mode".to_string()
+                                }
+                                0 => "No hints left".to_string(),
+                                n => format!("Hint (/), {} left", n),
+                            },
+                            screen_width,
+                            hint_y,
+                        );
+                        for (i, hint) in state.game.hints.iter().rev().enumerate() {
+                            utils::draw_text_centered(
+                                &mut d_,
+                                &state.font,
+                                font_size * 0.6,
+                                &state.game.describe_hint(hint),
+                                screen_width,
+                                hint_y - font_size * (i + 1) as f32,
+                                1.0,
+                            );
+                        }
+                        if ask_hint {
+                            let _ = state.game.handle(Input::Hint);
+                        }
 
                         for row in &state.keys {
                             let boost = 10 - row.len();
                             let mut x = 4 + ((key_width / 2) * (boost) as i32);
-                            let x_ = x;
 
                             for key in row {
-                                let color = {
-                                    if l.contains(key) {
-                                        if state.word.contains(key) {
-                                            Color::new(128, 128, 128, 255)
-                                        } else {
-                                            Color::BLACK
-                                        }
-                                    } else {
-                                        Color::new(74, 74, 74, 255)
-                                    }
+                                let color = match key
+                                    .chars()
+                                    .next()
+                                    .and_then(|ch| letter_states.get(&ch))
+                                {
+                                    Some(Feedback::Correct) => GREEN,
+                                    Some(Feedback::Present) => YELLOW,
+                                    Some(Feedback::Absent) => Color::BLACK,
+                                    None => Color::new(74, 74, 74, 255),
                                 };
                                 d_.draw_rectangle(x, y, key_width - 12, key_height - 12, color);
 
@@ -341,23 +745,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                                 if d_.is_gesture_detected(Gesture::GESTURE_TAP) {
                                     if mx >= x && mx <= x + key_width {
                                         if my >= y && my <= y + key_height {
-                                            let k = utils::get_key(&key);
-                                            if k != KeyboardKey::KEY_NULL {
-                                                state.buffer.push(k);
-                                            } else {
-                                                // BACKSPACE
-                                                if x == x_ as i32 {
-                                                    if state.buffer.len() == 5 && valid {
-                                                        let mut g = String::new();
-                                                        for key in &state.buffer {
-                                                            g += utils::get_letter(key);
-                                                        }
-                                                        state.guessed.push(g.to_uppercase());
-                                                        state.buffer.truncate(0);
-                                                    }
-                                                }
-                                                if x == x_ + (key_width * 8) {
-                                                    state.buffer.pop();
+                                            if let Some(input) = utils::tapped_input(key) {
+                                                let _ = state.game.handle(input);
+                                                if state.game.is_over() {
+                                                    state.end_time = SystemTime::now();
                                                 }
                                             }
                                         }
@@ -371,10 +762,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     }
                     _ => {
                         state.show_letters = false;
-                        if state.win_time.elapsed()?.as_secs() >= 1 {
+                        if state.end_time.elapsed()?.as_secs() >= 1 {
                             let alpha = {
-                                if state.win_time.elapsed()?.as_secs() <= 1 {
-                                    state.win_time.elapsed()?.as_secs_f32() - 1.0
+                                if state.end_time.elapsed()?.as_secs() <= 1 {
+                                    state.end_time.elapsed()?.as_secs_f32() - 1.0
                                 } else {
                                     1.0
                                 }
@@ -387,30 +778,249 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                                 Color::BLACK.fade(alpha * 0.50),
                             );
                             let f_width = f_width * 0.50;
-                            utils::draw_text_centered(
-                                &mut d_,
-                                &state.font,
-                                f_width,
-                                format!("Codle {}/6", state.guessed.len()).as_str(),
-                                screen_width,
-                                (screen_height / 4) as f32,
-                                alpha,
-                            );
+                            let mut lines =
+                                vec![format!("{} {}", state.game.title(), state.game.result())];
+                            if state.game.screen == Screen::Lost {
+                                let answers: Vec<&str> = state
+                                    .game
+                                    .boards
+                                    .iter()
+                                    .map(|b| b.answer.as_str())
+                                    .collect();
+                                lines.push(match answers.as_slice() {
+                                    [answer] => format!("The word was {}", answer),
+                                    _ => format!("The words were {}", answers.join(", ")),
+                                });
+                                lines.push(format!("{} guesses used", state.game.guesses));
+                            }
+                            if let [board] = state.game.boards.as_slice() {
+                                if board.remaining.len() > 1 {
+                                    let counts: Vec<String> =
+                                        board.remaining.iter().map(|n| n.to_string()).collect();
+                                    lines.push(format!("Words left: {}", counts.join(", ")));
+                                }
+                            }
+                            match state.game.hints.len() {
+                                0 => {}
+                                1 => lines.push("1 hint used".to_string()),
+                                n => lines.push(format!("{} hints used", n)),
+                            }
+                            if let Some(time) = state.game.finished.last().and_then(|f| f.time) {
+                                let splits: Vec<String> = state
+                                    .game
+                                    .timer
+                                    .splits
+                                    .iter()
+                                    .map(|s| format!("{:.1}", s.as_secs_f32()))
+                                    .collect();
+                                lines.push(format!("Splits {}", splits.join("  ")));
+                                lines.push(match state.stats.best_time(&state.game.settings) {
+                                    Some(best) if best.as_millis() == time.as_millis() => {
+                                        format!("Time {}, a new PB!", speedrun::format_time(time))
+                                    }
+                                    Some(best) => format!(
+                                        "Time {}  PB {}",
+                                        speedrun::format_time(time),
+                                        speedrun::format_time(best)
+                                    ),
+                                    None => format!("Time {}", speedrun::format_time(time)),
+                                });
+                            }
+                            let stats = state.stats.get(&state.game.settings);
+                            lines.push(format!(
+                                "Played {}  Win {}%  Streak {}",
+                                stats.played,
+                                stats.win_percentage(),
+                                stats.current_streak
+                            ));
+                            if stats.hinted > 0 {
+                                lines.push(format!("{} played with hints", stats.hinted));
+                            }
+                            if state.game.settings.mode == Mode::Practice {
+                                lines.push("Press N for another word".to_string());
+                            } else if state.game.is_stale() {
+                                lines.push("Press N for the new Codle".to_string());
+                            } else {
+                                lines.push(format!(
+                                    "Next Codle in {}",
+                                    utils::format_countdown(
+                                        state.game.settings.clock.time_until_next_day()
+                                    )
+                                ));
+                            }
+                            match missed.len() {
+                                0 => {}
+                                1 => lines.push("Press L for the other word that fit".to_string()),
+                                n => lines
+                                    .push(format!("Press L for the {} other words that fit", n)),
+                            }
+                            lines.push("Press R to review your guesses".to_string());
+                            lines.push("Press C to copy your result".to_string());
+                            lines.push("ioi-xd.net/codle".to_string());
 
-                            utils::draw_text_centered(
+                            let mut y = (screen_height / 4) as f32;
+                            let card_prompt = match state.card {
+                                Some(i) => {
+                                    utils::draw_card(
+                                        &mut d_,
+                                        &state.font,
+                                        f_width,
+                                        &state.game.boards[i].answer,
+                                        (screen_width / 4) as f32 + 16.0,
+                                        y,
+                                        (screen_width / 2) as f32 - 32.0,
+                                        alpha,
+                                    );
+                                    if i + 1 < state.game.boards.len() {
+                                        "Press E for the next word".to_string()
+                                    } else {
+                                        "Press E to go back".to_string()
+                                    }
+                                }
+                                None if review.is_some() => {
+                                    let rows = review.as_deref().unwrap_or_default();
+                                    let i = state.review.unwrap_or(0);
+                                    let (skill, luck) = solver::summary(rows);
+                                    utils::draw_text_centered(
+                                        &mut d_,
+                                        &state.font,
+                                        f_width,
+                                        &format!(
+                                            "{}: skill {}, luck {}",
+                                            state.game.boards[i].answer, skill, luck
+                                        ),
+                                        screen_width,
+                                        y,
+                                        alpha,
+                                    );
+                                    y += f_width * 2.0;
+                                    let small = f_width * 0.6;
+                                    for row in rows {
+                                        utils::draw_text_centered(
+                                            &mut d_,
+                                            &state.font,
+                                            small,
+                                            &row.to_string(),
+                                            screen_width,
+                                            y,
+                                            alpha,
+                                        );
+                                        y += small * 1.5;
+                                    }
+                                    y += small;
+                                    utils::draw_text_centered(
+                                        &mut d_,
+                                        &state.font,
+                                        small,
+                                        "Press C to copy the review",
+                                        screen_width,
+                                        y,
+                                        alpha,
+                                    );
+                                    if i + 1 < state.game.boards.len() {
+                                        "Press R for the next board".to_string()
+                                    } else {
+                                        "Press R to go back".to_string()
+                                    }
+                                }
+                                None if state.show_left => {
+                                    utils::draw_text_centered(
+                                        &mut d_,
+                                        &state.font,
+                                        f_width,
+                                        "It could also have been",
+                                        screen_width,
+                                        y,
+                                        alpha,
+                                    );
+                                    y += f_width * 2.0;
+                                    let small = f_width * 0.6;
+                                    for line in utils::wrap_text(
+                                        &state.font,
+                                        small,
+                                        &match missed.len() {
+                                            0..=60 => missed.join(" "),
+                                            n => format!(
+                                                "{} and {} more",
+                                                missed[..60].join(" "),
+                                                n - 60
+                                            ),
+                                        },
+                                        (screen_width / 2) as f32 - 32.0,
+                                    ) {
+                                        utils::draw_text_centered(
+                                            &mut d_,
+                                            &state.font,
+                                            small,
+                                            &line,
+                                            screen_width,
+                                            y,
+                                            alpha,
+                                        );
+                                        y += small * 1.2;
+                                    }
+                                    "Press L to go back".to_string()
+                                }
+                                None => {
+                                    // squeeze up a bit when there's a lot to say
+                                    let spacing = if lines.len() > 7 { 1.5 } else { 2.0 };
+                                    for line in &lines {
+                                        utils::draw_text_centered(
+                                            &mut d_,
+                                            &state.font,
+                                            f_width,
+                                            line,
+                                            screen_width,
+                                            y,
+                                            alpha,
+                                        );
+                                        y += f_width * spacing;
+                                    }
+                                    match state.game.boards.as_slice() {
+                                        [board] => {
+                                            format!("Press E to learn about {}", board.answer)
+                                        }
+                                        _ => "Press E to learn about the words".to_string(),
+                                    }
+                                }
+                            };
+                            next_card |= utils::draw_option(
                                 &mut d_,
                                 &state.font,
                                 f_width,
-                                "ioi-xd.net/codle",
+                                &card_prompt,
                                 screen_width,
-                                (screen_height / 4) as f32 + (f_width * 2.0),
-                                alpha,
-                            )
+                                (screen_height - screen_height / 8) as f32,
+                            );
                         }
                     }
                 }
             }
         }
+
+        // the prompt at the bottom goes on to whatever it says
+        if next_card {
+            if state.review.is_some() {
+                state.next_review();
+            } else if state.show_left {
+                state.show_left = false;
+            } else {
+                state.next_card();
+            }
+        }
+        if new_day {
+            state.restart();
+            let _ = state.game.handle(Input::Tap);
+        }
+        if state.recorded < state.game.finished.len() {
+            for finished in &state.game.finished[state.recorded..] {
+                state.stats.record(&state.game.settings, finished);
+            }
+            state.recorded = state.game.finished.len();
+            if let Err(e) = state.stats.save() {
+                eprintln!("couldn't save stats: {}", e);
+            }
+        }
     }
 
     Ok(())
@@ -1,6 +1,27 @@
 use raylib::prelude::*;
 
-use crate::GRAY;
+use codle::score::Feedback;
+
+use crate::{GRAY, GREEN, YELLOW};
+
+/// Which colour a letter cube gets drawn in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Plain,
+    Green,
+    Yellow,
+    Red,
+}
+
+impl From<Feedback> for Tile {
+    fn from(f: Feedback) -> Self {
+        match f {
+            Feedback::Correct => Tile::Green,
+            Feedback::Present => Tile::Yellow,
+            Feedback::Absent => Tile::Plain,
+        }
+    }
+}
 
 pub struct ModelStore {
     pub letters: Vec<(Model, Model)>,
@@ -66,8 +87,8 @@ impl ModelStore {
                         let mut _d = d_.begin_texture_mode(&thread, &mut letter.0);
                         match n {
                             0 => _d.clear_background(GRAY),
-                            1 => _d.clear_background(Color::new(0, 204, 136, 255)),
-                            2 => _d.clear_background(Color::new(255, 204, 0, 255)),
+                            1 => _d.clear_background(GREEN),
+                            2 => _d.clear_background(YELLOW),
                             3 => _d.clear_background(Color::new(204, 0, 0, 255)),
                             _ => _d.clear_background(Color::BLACK),
                         }
@@ -76,8 +97,8 @@ impl ModelStore {
                         let mut _d = d_.begin_texture_mode(&thread, &mut letter.1);
                         match n {
                             0 => _d.clear_background(GRAY),
-                            1 => _d.clear_background(Color::new(0, 204, 136, 255)),
-                            2 => _d.clear_background(Color::new(255, 204, 0, 255)),
+                            1 => _d.clear_background(GREEN),
+                            2 => _d.clear_background(YELLOW),
                             3 => _d.clear_background(Color::new(204, 0, 0, 255)),
                             _ => _d.clear_background(Color::BLACK),
                         }
@@ -129,3 +150,16 @@ impl ModelStore {
         })
     }
 }
+
+impl ModelStore {
+    /// The (blank, lettered) pair of cubes for `ch`.
+    pub fn letter(&self, tile: Tile, ch: char) -> Option<&(Model, Model)> {
+        let set = match tile {
+            Tile::Plain => &self.letters,
+            Tile::Green => &self.green_letters,
+            Tile::Yellow => &self.yellow_letters,
+            Tile::Red => &self.red_letters,
+        };
+        (ch as usize).checked_sub(65).and_then(|i| set.get(i))
+    }
+}
@@ -0,0 +1,246 @@
+//! Word packs: answer and guess lists that are loaded at runtime instead of
+//! being baked in, so anyone can play with their own service names or DSL
+//! keywords without recompiling. A pack is a JSON file like
+//!
+//! ```json
+//! {
+//!     "name": "Acme services",
+//!     "author": "Platform team",
+//!     "word_length": 5,
+//!     "answers": ["relay", {"word": "vault", "description": "Holds secrets."}],
+//!     "guesses": ["proxy", "queue"]
+//! }
+//! ```
+//!
+//! where every answer and guess is written like an entry in
+//! `dictionary.json`. Only `author` and `guesses` can be left out. Native
+//! builds pick up every pack in `~/.codle_packs/`, plus any given with
+//! `--pack`.
+
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+use crate::{
+    dictionary::{Dictionary, Entry},
+    game::{MAX_WORD_LENGTH, MIN_WORD_LENGTH},
+};
+
+lazy_static::lazy_static! {
+    /// Every pack that's been loaded, in the order they're offered.
+    static ref PACKS: Mutex<Vec<&'static Pack>> = Mutex::new(Vec::new());
+}
+
+#[derive(Debug)]
+pub struct Pack {
+    /// Used as the key for anything saved per pack, and for seeding. Made
+    /// from the name, so renaming a pack starts it over.
+    pub id: String,
+    pub name: String,
+    pub author: Option<String>,
+    /// Every answer and guess has exactly this many letters.
+    pub word_length: usize,
+    pub answers: Dictionary,
+    /// Accepted on top of the answers and the usual English words.
+    pub guesses: Dictionary,
+}
+
+// packs are told apart by id, since the same file can be loaded twice
+impl PartialEq for Pack {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Pack {}
+
+impl std::hash::Hash for Pack {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Pack {
+    /// Parses and checks a pack file. Unlike the bundled dictionaries,
+    /// nothing is quietly dropped: a word that can't be played is an error
+    /// saying which one and why.
+    pub fn parse(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let v: Value = serde_json::from_str(json)?;
+        if !v.is_object() {
+            return Err(
+                "a pack should be an object with a \"name\", \"word_length\" and \"answers\""
+                    .into(),
+            );
+        }
+        let text = |key: &str| match v.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) if !s.trim().is_empty() => Ok(Some(s.trim().to_string())),
+            Some(_) => Err(format!("\"{}\" should be some text", key)),
+        };
+        let name = text("name")?.ok_or("missing \"name\"")?;
+        if !name.chars().any(|c| c.is_ascii_alphanumeric()) {
+            return Err(
+                "\"name\" needs at least one letter or number from a to z or 0 to 9".into(),
+            );
+        }
+        let author = text("author")?;
+        let word_length = v
+            .get("word_length")
+            .ok_or("missing \"word_length\"")?
+            .as_u64()
+            .ok_or("\"word_length\" should be a number")? as usize;
+        if !(MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&word_length) {
+            return Err(format!(
+                "\"word_length\" should be between {} and {}, not {}",
+                MIN_WORD_LENGTH, MAX_WORD_LENGTH, word_length
+            )
+            .into());
+        }
+        let answers = Pack::parse_words(&v, "answers", word_length)?;
+        if answers.entries.is_empty() {
+            return Err("\"answers\" is empty".into());
+        }
+        let guesses = Pack::parse_words(&v, "guesses", word_length)?;
+        let id = name
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        Ok(Pack {
+            id: format!("pack-{}", id),
+            name,
+            author,
+            word_length,
+            answers,
+            guesses,
+        })
+    }
+
+    /// Reads the list under `key`, which is allowed to be missing.
+    fn parse_words(v: &Value, key: &str, word_length: usize) -> Result<Dictionary, String> {
+        let mut dictionary = Dictionary::default();
+        let values = match v.get(key) {
+            None | Some(Value::Null) => return Ok(dictionary),
+            Some(Value::Array(values)) => values,
+            Some(_) => return Err(format!("\"{}\" should be a list", key)),
+        };
+        for (i, v) in values.iter().enumerate() {
+            let mut entry = Entry::parse(v).map_err(|e| format!("{}[{}]: {}", key, i, e))?;
+            if !entry.word.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(format!(
+                    "{}[{}]: \"{}\" should only have the letters a to z",
+                    key, i, entry.word
+                ));
+            }
+            if entry.word.len() != word_length {
+                return Err(format!(
+                    "{}[{}]: \"{}\" has {} letters, but \"word_length\" is {}",
+                    key,
+                    i,
+                    entry.word,
+                    entry.word.len(),
+                    word_length
+                ));
+            }
+            entry.word = entry.word.to_lowercase().into();
+            dictionary.insert(entry);
+        }
+        Ok(dictionary)
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    pub fn load(path: &std::path::Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let json = std::fs::read_to_string(path)?;
+        Pack::parse(&json)
+    }
+}
+
+/// Makes a pack available for the rest of the run. Adding one that's already
+/// there (by id) replaces it, so `--pack` always plays the file it was given.
+pub fn add(pack: Pack) -> &'static Pack {
+    let mut packs = PACKS.lock().unwrap();
+    let pack = Box::leak(Box::new(pack));
+    match packs.iter_mut().find(|p| p.id == pack.id) {
+        Some(p) => *p = pack,
+        None => packs.push(pack),
+    }
+    pack
+}
+
+pub fn all() -> Vec<&'static Pack> {
+    PACKS.lock().unwrap().clone()
+}
+
+/// Where native builds look for packs to offer in the menu.
+#[cfg(not(feature = "wasm"))]
+pub fn dir() -> std::path::PathBuf {
+    let home = std::env::var_os("HOME")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_default();
+    home.join(".codle_packs")
+}
+
+/// Adds every pack in [`dir`]. One that doesn't load is left out, with the
+/// reason printed.
+#[cfg(not(feature = "wasm"))]
+pub fn load_dir() {
+    let Ok(files) = std::fs::read_dir(dir()) else {
+        return;
+    };
+    let mut paths: Vec<_> = files
+        .filter_map(|f| f.ok())
+        .map(|f| f.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        match Pack::load(&path) {
+            Ok(pack) => {
+                if let Some(p) = all().iter().find(|p| p.id == pack.id) {
+                    eprintln!(
+                        "{} replaces \"{}\", which has the same id",
+                        path.display(),
+                        p.name
+                    );
+                }
+                add(pack);
+            }
+            Err(e) => eprintln!("skipping {}: {}", path.display(), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(name: &str, answers: &str) -> Result<Pack, Box<dyn std::error::Error>> {
+        Pack::parse(&format!(
+            r#"{{"name": "{}", "word_length": 5, "answers": [{}]}}"#,
+            name, answers
+        ))
+    }
+
+    #[test]
+    fn id_comes_from_the_name() {
+        assert_eq!(
+            pack("My Cool Pack!", r#""array""#).unwrap().id,
+            "pack-my-cool-pack"
+        );
+        assert!(pack("!!!", r#""array""#).is_err());
+    }
+
+    #[test]
+    fn adding_the_same_id_replaces_it() {
+        add(pack("Replace Me", r#""array""#).unwrap());
+        let p = add(pack("replace me", r#""error""#).unwrap());
+        assert_eq!(p.answers.entries[0].word, "error");
+        let same: Vec<_> = all()
+            .into_iter()
+            .filter(|p| p.id == "pack-replace-me")
+            .collect();
+        assert_eq!(same.len(), 1);
+        assert_eq!(same[0].answers.entries[0].word, "error");
+    }
+}
@@ -0,0 +1,83 @@
+//! Extra rules a guess has to follow before it's accepted, on top of being
+//! in the dictionary.
+
+use std::{collections::HashMap, fmt};
+
+use crate::{game::Row, score::Feedback};
+
+/// Why a guess breaks hard mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HardModeViolation {
+    /// A letter that was green has to stay where it is.
+    MissingGreen { position: usize, letter: char },
+    /// A letter that was green or yellow has to be used again, at least as
+    /// many times as it has been revealed in a single row.
+    MissingLetter { letter: char, count: usize },
+}
+
+impl fmt::Display for HardModeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HardModeViolation::MissingGreen { position, letter } => {
+                write!(f, "{} letter must be {}", ordinal(position + 1), letter)
+            }
+            HardModeViolation::MissingLetter { letter, count: 1 } => {
+                write!(f, "Guess must contain {}", letter)
+            }
+            HardModeViolation::MissingLetter { letter, count } => {
+                write!(f, "Guess must contain {} {}'s", count, letter)
+            }
+        }
+    }
+}
+
+pub(crate) fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Checks `guess` against every hint revealed in `rows`. Greens are checked
+/// first, left to right, so the player gets told about the earliest problem.
+pub fn check_hard_mode(rows: &[Row], guess: &str) -> Result<(), HardModeViolation> {
+    let guess: Vec<char> = guess.chars().map(|c| c.to_ascii_uppercase()).collect();
+
+    for row in rows {
+        for (position, (letter, f)) in row.word.chars().zip(&row.feedback).enumerate() {
+            if *f == Feedback::Correct && guess.get(position) != Some(&letter) {
+                return Err(HardModeViolation::MissingGreen { position, letter });
+            }
+        }
+    }
+
+    let mut required: Vec<(char, usize)> = Vec::new();
+    for row in rows {
+        let mut revealed: HashMap<char, usize> = HashMap::new();
+        for (letter, f) in row.word.chars().zip(&row.feedback) {
+            if *f != Feedback::Absent {
+                *revealed.entry(letter).or_insert(0) += 1;
+            }
+        }
+        for letter in row.word.chars() {
+            if let Some(count) = revealed.remove(&letter) {
+                match required.iter_mut().find(|(l, _)| *l == letter) {
+                    Some((_, c)) => *c = (*c).max(count),
+                    None => required.push((letter, count)),
+                }
+            }
+        }
+    }
+
+    for (letter, count) in required {
+        if guess.iter().filter(|c| **c == letter).count() < count {
+            return Err(HardModeViolation::MissingLetter { letter, count });
+        }
+    }
+
+    Ok(())
+}
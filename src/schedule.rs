@@ -0,0 +1,292 @@
+//! Decides which answer comes up on which day.
+//!
+//! Every word list (and length) has a fixed schedule: the answers are
+//! shuffled into a permutation, played through one per day, then shuffled
+//! again into a new permutation for the next cycle. So an answer can only come
+//! back after every other answer has had its turn, and never twice in a row
+//! across a cycle boundary.
+//!
+//! The shuffle uses our own SplitMix64 generator rather than anything from
+//! `rand`, so the schedule is the same on every build and target no matter
+//! what version of `rand` is linked in. Changing a word list (adding or
+//! removing answers) does reshuffle it.
+
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Day #1 (2024-01-01), counted in days since the unix epoch.
+pub const LAUNCH_DAY: u64 = 19723;
+
+pub const DAY_MILLIS: u128 = 86400000;
+
+/// The number in "Codle #N" for a given day, counting from 1 on
+/// [`LAUNCH_DAY`]. A clock set before launch just gets #1.
+pub fn puzzle_number(day: u64) -> u64 {
+    day.saturating_sub(LAUNCH_DAY) + 1
+}
+
+/// Decides when one day ends and the next begins. Days roll over at midnight
+/// in whatever UTC offset the clock has, so nobody gets a new word halfway
+/// through their afternoon.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Clock {
+    /// Minutes east of UTC.
+    pub offset_minutes: i32,
+}
+
+impl Clock {
+    pub const MIN_OFFSET: i32 = -12 * 60;
+    pub const MAX_OFFSET: i32 = 14 * 60;
+
+    pub fn new(offset_minutes: i32) -> Self {
+        Self {
+            offset_minutes: offset_minutes.clamp(Self::MIN_OFFSET, Self::MAX_OFFSET),
+        }
+    }
+
+    /// The browser's time zone on the web. Natively there's no portable way
+    /// to ask, so it's whatever `CODLE_UTC_OFFSET` says, or UTC.
+    #[cfg(not(feature = "wasm"))]
+    pub fn local() -> Self {
+        std::env::var("CODLE_UTC_OFFSET")
+            .ok()
+            .and_then(|s| Self::parse(&s))
+            .unwrap_or_default()
+    }
+
+    #[cfg(feature = "wasm")]
+    pub fn local() -> Self {
+        extern "C" {
+            fn emscripten_run_script_int(script: *const std::ffi::c_char) -> i32;
+        }
+        let script = std::ffi::CString::new("-new Date().getTimezoneOffset()").unwrap();
+        Self::new(unsafe { emscripten_run_script_int(script.as_ptr()) })
+    }
+
+    /// Reads an offset like `+2`, `-05:30`, `+0530` or `UTC+9`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let s = s.strip_prefix("UTC").unwrap_or(s);
+        if s.is_empty() {
+            return Some(Self::default());
+        }
+        let (sign, s) = match s.as_bytes()[0] {
+            b'+' => (1, &s[1..]),
+            b'-' => (-1, &s[1..]),
+            _ => (1, s),
+        };
+        // splitting anything but plain digits could land inside a character
+        if !s.bytes().all(|b| b.is_ascii_digit() || b == b':') {
+            return None;
+        }
+        let (hours, minutes) = match s.split_once(':') {
+            Some((h, m)) => (h, m),
+            None if s.len() == 4 => s.split_at(2),
+            None => (s, "0"),
+        };
+        if hours.len() > 2 || minutes.len() > 2 {
+            return None;
+        }
+        let hours: i32 = hours.parse().ok()?;
+        let minutes: i32 = minutes.parse().ok()?;
+        if minutes >= 60 {
+            return None;
+        }
+        let offset = sign * (hours * 60 + minutes);
+        (Self::MIN_OFFSET..=Self::MAX_OFFSET)
+            .contains(&offset)
+            .then(|| Self::new(offset))
+    }
+
+    /// Moves the rollover an hour east, wrapping around after the last time
+    /// zone.
+    pub fn next_offset(&self) -> Self {
+        match self.offset_minutes + 60 {
+            o if o > Self::MAX_OFFSET => Self::new(Self::MIN_OFFSET),
+            o => Self::new(o),
+        }
+    }
+
+    /// e.g. `UTC`, `UTC+2` or `UTC-5:30`.
+    pub fn name(&self) -> String {
+        let o = self.offset_minutes;
+        let sign = if o < 0 { '-' } else { '+' };
+        match (o.abs() / 60, o.abs() % 60) {
+            (0, 0) => "UTC".to_string(),
+            (h, 0) => format!("UTC{}{}", sign, h),
+            (h, m) => format!("UTC{}{}:{:02}", sign, h, m),
+        }
+    }
+
+    /// Milliseconds since the unix epoch, shifted into this clock's time zone.
+    fn now_millis(&self) -> u128 {
+        let millis = UNIX_EPOCH.elapsed().unwrap().as_millis() as i128;
+        (millis + self.offset_minutes as i128 * 60_000).max(0) as u128
+    }
+
+    /// Days since the unix epoch in this clock's time zone.
+    pub fn today(&self) -> u64 {
+        (self.now_millis() / DAY_MILLIS) as u64
+    }
+
+    /// How long until [`Clock::today`] ticks over.
+    pub fn time_until_next_day(&self) -> Duration {
+        let millis = self.now_millis();
+        Duration::from_millis((DAY_MILLIS - millis % DAY_MILLIS) as u64)
+    }
+}
+
+/// The SplitMix64 finalizer. Spreads similar inputs (like consecutive days)
+/// out into unrelated looking numbers.
+pub fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// Folds a few numbers (list, length, mode...) into one seed.
+pub fn seed(parts: &[u64]) -> u64 {
+    parts.iter().fold(0, |acc, p| mix(acc ^ p))
+}
+
+/// `0..len` in shuffled order, using a Fisher-Yates shuffle.
+fn permutation(len: usize, seed: u64) -> Vec<usize> {
+    let mut state = seed;
+    let mut order: Vec<usize> = (0..len).collect();
+    for i in (1..len).rev() {
+        state = state.wrapping_add(0x9e3779b97f4a7c15);
+        // the modulo bias is tiny for lists this size and doesn't matter here
+        let j = (mix(state) % (i as u64 + 1)) as usize;
+        order.swap(i, j);
+    }
+    order
+}
+
+/// Which of `len` answers is the `n`th one in the schedule for `seed`.
+pub fn nth(len: usize, seed: u64, n: u64) -> usize {
+    let len64 = len as u64;
+    let cycle = n / len64;
+    let mut order = permutation(len, mix(seed ^ cycle));
+    // don't let the last answer of one cycle be the first of the next
+    if cycle > 0 && len > 2 {
+        let previous = permutation(len, mix(seed ^ (cycle - 1)));
+        if order[0] == previous[len - 1] {
+            order.swap(0, 1);
+        }
+    }
+    order[(n % len64) as usize]
+}
+
+/// The answers for game `n` of a schedule where every game has `count`
+/// boards: entries `n * count` onwards. A game that runs from the end of one
+/// cycle into the next could get a word twice, so anything already picked is
+/// skipped for the next entry along.
+pub fn group(len: usize, seed: u64, n: u64, count: usize) -> Vec<usize> {
+    let mut picks = Vec::with_capacity(count);
+    let mut next = n * count as u64;
+    while picks.len() < count {
+        let i = nth(len, seed, next);
+        next += 1;
+        // a list shorter than the game can't help repeating
+        if !picks.contains(&i) || picks.len() >= len {
+            picks.push(i);
+        }
+    }
+    picks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_repeats_within_a_cycle() {
+        let len = 50;
+        for cycle in 0..4 {
+            let mut seen: Vec<usize> = (0..len as u64)
+                .map(|i| nth(len, 7, cycle * len as u64 + i))
+                .collect();
+            seen.sort();
+            assert_eq!(seen, (0..len).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn no_repeat_across_cycles() {
+        for seed in 0..200 {
+            assert_ne!(nth(5, seed, 4), nth(5, seed, 5));
+        }
+    }
+
+    #[test]
+    fn no_repeats_within_a_group() {
+        for seed in 0..50 {
+            for n in 0..20 {
+                let mut picks = group(10, seed, n, 4);
+                picks.sort();
+                picks.dedup();
+                assert_eq!(picks.len(), 4, "seed {} game {}", seed, n);
+            }
+        }
+    }
+
+    #[test]
+    fn groups_follow_the_schedule() {
+        let picks: Vec<usize> = (0..3).map(|i| nth(10, 7, 3 + i)).collect();
+        assert_eq!(group(10, 7, 1, 3), picks);
+    }
+
+    #[test]
+    fn parse_offsets() {
+        let minutes = |s| Clock::parse(s).map(|c| c.offset_minutes);
+        assert_eq!(minutes("+2"), Some(120));
+        assert_eq!(minutes("-05:30"), Some(-330));
+        assert_eq!(minutes("+0530"), Some(330));
+        assert_eq!(minutes("UTC+9"), Some(540));
+        assert_eq!(minutes(" UTC "), Some(0));
+        assert_eq!(minutes("+14"), Some(Clock::MAX_OFFSET));
+        assert_eq!(minutes("-12"), Some(Clock::MIN_OFFSET));
+    }
+
+    #[test]
+    fn parse_rejects_garbage() {
+        for s in [
+            "+15",
+            "-13",
+            "+5:60",
+            "abc",
+            "1é2",
+            "--5",
+            "+1:2:3",
+            "999999999",
+            "+",
+            ":",
+        ] {
+            assert_eq!(Clock::parse(s), None, "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(Clock::new(0).name(), "UTC");
+        assert_eq!(Clock::new(120).name(), "UTC+2");
+        assert_eq!(Clock::new(-330).name(), "UTC-5:30");
+    }
+
+    #[test]
+    fn next_offset_wraps_around() {
+        assert_eq!(Clock::new(0).next_offset(), Clock::new(60));
+        assert_eq!(Clock::new(-330).next_offset(), Clock::new(-270));
+        assert_eq!(
+            Clock::new(Clock::MAX_OFFSET).next_offset(),
+            Clock::new(Clock::MIN_OFFSET)
+        );
+    }
+
+    #[test]
+    fn puzzle_numbers_start_at_one() {
+        assert_eq!(puzzle_number(LAUNCH_DAY), 1);
+        assert_eq!(puzzle_number(LAUNCH_DAY + 9), 10);
+        assert_eq!(puzzle_number(0), 1);
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+/// How a single letter of a guess relates to the answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Feedback {
+    /// Letter isn't in the answer (or every copy of it is already accounted for).
+    Absent,
+    /// Letter is in the answer but somewhere else.
+    Present,
+    /// Letter is in the right spot.
+    Correct,
+}
+
+/// Scores `guess` against `answer` with the usual Wordle rules for repeated
+/// letters: exact matches are claimed first, then whatever copies of a letter
+/// are left over in the answer get handed out as yellows from left to right.
+///
+/// Both words are compared case-insensitively. If the lengths differ, the
+/// extra letters of the guess are scored as absent.
+pub fn score_guess(answer: &str, guess: &str) -> Vec<Feedback> {
+    let answer: Vec<char> = answer.chars().map(|c| c.to_ascii_uppercase()).collect();
+    let guess: Vec<char> = guess.chars().map(|c| c.to_ascii_uppercase()).collect();
+
+    let mut feedback = vec![Feedback::Absent; guess.len()];
+    let mut leftover: HashMap<char, usize> = HashMap::new();
+
+    for (i, ch) in answer.iter().enumerate() {
+        if guess.get(i) == Some(ch) {
+            feedback[i] = Feedback::Correct;
+        } else {
+            *leftover.entry(*ch).or_insert(0) += 1;
+        }
+    }
+
+    for (i, ch) in guess.iter().enumerate() {
+        if feedback[i] == Feedback::Correct {
+            continue;
+        }
+        if let Some(n) = leftover.get_mut(ch) {
+            if *n > 0 {
+                *n -= 1;
+                feedback[i] = Feedback::Present;
+            }
+        }
+    }
+
+    feedback
+}
+
+pub fn is_solved(feedback: &[Feedback]) -> bool {
+    !feedback.is_empty() && feedback.iter().all(|f| *f == Feedback::Correct)
+}
+
+/// The best thing we know about every letter that has been guessed so far,
+/// which is what the on-screen keyboard shows.
+pub fn letter_states<'a, I>(rows: I) -> HashMap<char, Feedback>
+where
+    I: IntoIterator<Item = (&'a str, &'a [Feedback])>,
+{
+    let mut states = HashMap::new();
+    for (guess, feedback) in rows {
+        for (ch, f) in guess.chars().zip(feedback.iter().copied()) {
+            let best = states.entry(ch.to_ascii_uppercase()).or_insert(f);
+            if f > *best {
+                *best = f;
+            }
+        }
+    }
+    states
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Feedback::*;
+
+    #[test]
+    fn extra_guess_letters_are_absent() {
+        // ARRAY has two R's but ERROR's other R is already used up by the
+        // greens, so the R's in the guess don't get a yellow each
+        assert_eq!(
+            score_guess("array", "error"),
+            vec![Absent, Correct, Correct, Absent, Absent]
+        );
+    }
+
+    #[test]
+    fn repeated_letters_only_get_one_yellow_each() {
+        assert_eq!(
+            score_guess("abbey", "babes"),
+            vec![Present, Present, Correct, Correct, Absent]
+        );
+        assert_eq!(
+            score_guess("abbey", "kebab"),
+            vec![Absent, Present, Correct, Present, Present]
+        );
+    }
+
+    #[test]
+    fn ignores_case() {
+        assert_eq!(score_guess("Error", "eRRoR"), vec![Correct; 5]);
+        assert!(is_solved(&score_guess("error", "ERROR")));
+    }
+
+    #[test]
+    fn keyboard_keeps_the_best_state() {
+        let rows = [
+            ("ERROR", score_guess("array", "error")),
+            ("RAYON", score_guess("array", "rayon")),
+        ];
+        let states = letter_states(rows.iter().map(|(w, f)| (*w, f.as_slice())));
+        assert_eq!(states[&'R'], Correct);
+        assert_eq!(states[&'A'], Present);
+        assert_eq!(states[&'E'], Absent);
+    }
+}
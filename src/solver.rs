@@ -0,0 +1,362 @@
+//! Works out the best next guess. Every guess splits the words the answer
+//! could still be into groups by the feedback it would get, and the more
+//! evenly it splits them the more it tells you: that's its expected
+//! information, in bits. The expected number of words left afterwards is
+//! worked out along the way, for ranking by that instead.
+//!
+//! After a game, [`review`] goes back over each row to see how it did
+//! against the best guess there was at the time.
+
+use std::fmt;
+
+use crate::{
+    game::{Row, MAX_WORD_LENGTH},
+    rules, score,
+};
+
+/// What to rank guesses by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Metric {
+    /// Most expected information first.
+    #[default]
+    Information,
+    /// Fewest expected candidates left first.
+    Remaining,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    pub word: &'static str,
+    /// Expected information, summed over every board.
+    pub bits: f64,
+    /// Expected candidates left afterwards, summed over every board.
+    pub remaining: f64,
+    /// Whether it could be the answer to one of the boards.
+    pub candidate: bool,
+}
+
+/// The words in `answers` that fit every row so far.
+pub fn candidates(answers: &[&'static str], rows: &[Row]) -> Vec<&'static str> {
+    answers
+        .iter()
+        .copied()
+        .filter(|w| {
+            rows.iter()
+                .all(|r| score::score_guess(w, &r.word) == r.feedback)
+        })
+        .collect()
+}
+
+/// Ranks every word in `pool` as the next guess, where `boards` has the
+/// candidates left for each unsolved board. With `hard_mode` on, guesses
+/// that break it on any board (given the rows for each in `rows`) are left
+/// out. Words that could be the answer win ties, since they might finish it.
+pub fn rank(
+    pool: &[&'static str],
+    boards: &[Vec<&'static str>],
+    rows: &[&[Row]],
+    hard_mode: bool,
+    by: Metric,
+) -> Vec<Suggestion> {
+    let mut suggestions: Vec<Suggestion> = pool
+        .iter()
+        .filter(|w| !hard_mode || rows.iter().all(|r| rules::check_hard_mode(r, w).is_ok()))
+        .map(|&word| {
+            let mut suggestion = Suggestion {
+                word,
+                bits: 0.0,
+                remaining: 0.0,
+                candidate: false,
+            };
+            for candidates in boards {
+                let (bits, remaining) = split(candidates, word);
+                suggestion.bits += bits;
+                suggestion.remaining += remaining;
+                suggestion.candidate |= candidates.contains(&word);
+            }
+            suggestion
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        let order = match by {
+            Metric::Information => b.bits.total_cmp(&a.bits),
+            Metric::Remaining => a.remaining.total_cmp(&b.remaining),
+        };
+        order
+            .then(b.candidate.cmp(&a.candidate))
+            .then(a.word.cmp(b.word))
+    });
+    suggestions
+}
+
+/// How a row went, see [`review`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowReview {
+    pub word: String,
+    /// Candidates before and after the guess.
+    pub before: usize,
+    pub after: usize,
+    /// Expected information from the guess, summed over every board it was
+    /// played on.
+    pub bits: f64,
+    /// The solver's pick at that point, if there was anything to pick.
+    pub best: Option<Suggestion>,
+    /// Out of 100: how the guess's expected information compares to the
+    /// best guess's.
+    pub skill: u32,
+    /// Out of 100: how many of the answers it could have been would have
+    /// left more candidates than this one did. 50 is about average.
+    pub luck: u32,
+}
+
+impl fmt::Display for RowReview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} -> {}  skill {}  luck {}",
+            self.word, self.before, self.after, self.skill, self.luck
+        )?;
+        if let Some(best) = self.best.as_ref().filter(|b| b.bits > self.bits) {
+            write!(f, "  (best {})", best.word.to_uppercase())?;
+        }
+        Ok(())
+    }
+}
+
+/// Goes over every row of every board, given what the answers could have
+/// been at the start. `pool` is everything that could have been guessed
+/// instead. A row is judged against every board it was played on, the same
+/// way [`rank`] picks guesses, so a guess aimed at one board still counts
+/// for what it told you about the others.
+pub fn review(
+    pool: &[&'static str],
+    answers: &[&'static str],
+    boards: &[&[Row]],
+    hard_mode: bool,
+) -> Vec<Vec<RowReview>> {
+    let mut candidates = vec![answers.to_vec(); boards.len()];
+    let mut reviews = vec![Vec::new(); boards.len()];
+    let rows = boards.iter().map(|rows| rows.len()).max().unwrap_or(0);
+    for i in 0..rows {
+        // boards stop getting rows once they're solved
+        let going: Vec<usize> = (0..boards.len()).filter(|&b| boards[b].len() > i).collect();
+        let word = &boards[going[0]][i].word;
+        let bits: f64 = going.iter().map(|&b| split(&candidates[b], word).0).sum();
+        let best = rank(
+            pool,
+            &going
+                .iter()
+                .map(|&b| candidates[b].clone())
+                .collect::<Vec<_>>(),
+            &going.iter().map(|&b| &boards[b][..i]).collect::<Vec<_>>(),
+            hard_mode,
+            Metric::Information,
+        )
+        .into_iter()
+        .next();
+        let skill = match best.as_ref().map(|b| b.bits) {
+            Some(best) if best > 0.0 => (bits / best * 100.0).round().min(100.0) as u32,
+            // every board is down to one word, so the only good guesses were those
+            _ if going.iter().all(|&b| candidates[b].len() == 1) => {
+                if going
+                    .iter()
+                    .any(|&b| candidates[b][0].eq_ignore_ascii_case(word))
+                {
+                    100
+                } else {
+                    0
+                }
+            }
+            _ => 100,
+        };
+
+        for &b in &going {
+            let (left, luck) = luck(&candidates[b], &boards[b][i]);
+            reviews[b].push(RowReview {
+                word: word.clone(),
+                before: candidates[b].len(),
+                after: left.len(),
+                bits,
+                best: best.clone(),
+                skill,
+                luck,
+            });
+            candidates[b] = left;
+        }
+    }
+    reviews
+}
+
+/// The candidates left after `row`, and how lucky the feedback was: out of
+/// 100, how many of the `candidates` would have left more than that.
+fn luck(candidates: &[&'static str], row: &Row) -> (Vec<&'static str>, u32) {
+    let left = self::candidates(candidates, std::slice::from_ref(row));
+    let sizes = groups(candidates, &row.word);
+    let (mut worse, mut same) = (0, 0);
+    for word in candidates {
+        let size = sizes[pattern(word, &row.word) as usize];
+        match size.cmp(&left.len()) {
+            std::cmp::Ordering::Greater => worse += 1,
+            std::cmp::Ordering::Equal => same += 1,
+            std::cmp::Ordering::Less => {}
+        }
+    }
+    let luck = match candidates.len() {
+        0 => 50,
+        n => ((worse as f64 + same as f64 / 2.0) / n as f64 * 100.0).round() as u32,
+    };
+    (left, luck)
+}
+
+/// Average skill and luck over a review.
+pub fn summary(reviews: &[RowReview]) -> (u32, u32) {
+    if reviews.is_empty() {
+        return (0, 0);
+    }
+    let n = reviews.len() as u32;
+    (
+        reviews.iter().map(|r| r.skill).sum::<u32>() / n,
+        reviews.iter().map(|r| r.luck).sum::<u32>() / n,
+    )
+}
+
+/// How many candidates get each feedback for `guess`, indexed by
+/// [`pattern`].
+fn groups(candidates: &[&str], guess: &str) -> Vec<usize> {
+    let mut groups = vec![0usize; 3usize.pow(guess.len() as u32)];
+    for word in candidates {
+        groups[pattern(word, guess) as usize] += 1;
+    }
+    groups
+}
+
+/// Expected information and candidates left from guessing `guess`.
+fn split(candidates: &[&str], guess: &str) -> (f64, f64) {
+    let n = candidates.len() as f64;
+    let mut bits = 0.0;
+    let mut remaining = 0.0;
+    for size in groups(candidates, guess)
+        .into_iter()
+        .filter(|size| *size > 0)
+    {
+        let p = size as f64 / n;
+        bits -= p * p.log2();
+        remaining += p * size as f64;
+    }
+    (bits, remaining)
+}
+
+/// [`score::score_guess`] packed into a number, without allocating, since
+/// this runs for every pair of words. Only works on ASCII letters, which is
+/// all any answer or guess has.
+fn pattern(answer: &str, guess: &str) -> u32 {
+    let (answer, guess) = (answer.as_bytes(), guess.as_bytes());
+    let mut leftover = [0u8; 26];
+    let mut correct = [false; MAX_WORD_LENGTH];
+    for (i, a) in answer.iter().enumerate() {
+        if guess[i].eq_ignore_ascii_case(a) {
+            correct[i] = true;
+        } else {
+            leftover[(a.to_ascii_lowercase() - b'a') as usize] += 1;
+        }
+    }
+    let mut pattern = 0;
+    for (i, g) in guess.iter().enumerate() {
+        let f = if correct[i] {
+            2
+        } else {
+            let n = &mut leftover[(g.to_ascii_lowercase() - b'a') as usize];
+            if *n > 0 {
+                *n -= 1;
+                1
+            } else {
+                0
+            }
+        };
+        pattern = pattern * 3 + f;
+    }
+    pattern
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDS: [&str; 4] = ["while", "whale", "white", "cloud"];
+
+    fn row(answer: &str, guess: &str) -> Row {
+        Row {
+            word: guess.to_uppercase(),
+            feedback: score::score_guess(answer, guess),
+        }
+    }
+
+    fn rows(answer: &str, guesses: &[&str]) -> Vec<Row> {
+        guesses.iter().map(|g| row(answer, g)).collect()
+    }
+
+    #[test]
+    fn rank_puts_the_best_split_first() {
+        let boards = [vec!["while", "whale", "white"]];
+        let ranked = rank(&WORDS, &boards, &[&[]], false, Metric::Information);
+        // whale, while and white all split all three, whale wins alphabetically
+        assert_eq!(ranked[0].word, "whale");
+        assert!(ranked[0].candidate);
+        assert!((ranked[0].bits - 3f64.log2()).abs() < 1e-9);
+        assert_eq!(ranked[2].bits, ranked[0].bits);
+        assert_eq!(ranked[3].word, "cloud");
+        assert!(ranked[3].bits < ranked[2].bits);
+
+        let ranked = rank(&WORDS, &boards, &[&[]], false, Metric::Remaining);
+        assert_eq!(ranked[0].remaining, 1.0);
+        assert_eq!(ranked[3].word, "cloud");
+    }
+
+    #[test]
+    fn rank_sums_over_boards() {
+        let boards = [vec!["while", "whale"], vec!["white", "cloud"]];
+        let ranked = rank(&WORDS, &boards, &[&[], &[]], false, Metric::Information);
+        assert_eq!(ranked[0].bits, 2.0);
+        assert!(ranked.iter().all(|s| s.candidate));
+    }
+
+    #[test]
+    fn rank_leaves_out_hard_mode_breakers() {
+        let played = [row("while", "cloud")];
+        let ranked = rank(
+            &WORDS,
+            &[vec!["while", "whale"]],
+            &[&played],
+            true,
+            Metric::Information,
+        );
+        let words: Vec<_> = ranked.iter().map(|s| s.word).collect();
+        assert_eq!(words, ["whale", "while", "cloud"]);
+    }
+
+    #[test]
+    fn review_one_board() {
+        let played = rows("while", &["cloud", "while"]);
+        let reviews = review(&WORDS, &WORDS, &[&played], false);
+        let r = &reviews[0];
+        assert_eq!((r[0].before, r[0].after), (4, 2));
+        assert_eq!(r[0].best.as_ref().unwrap().word, "whale");
+        assert_eq!(r[0].skill, 75);
+        assert_eq!((r[1].before, r[1].after, r[1].skill), (2, 1, 100));
+    }
+
+    #[test]
+    fn review_counts_rows_aimed_at_other_boards() {
+        let first = rows("cloud", &["could", "whale", "cloud"]);
+        let second = rows("while", &["could", "whale", "cloud", "while"]);
+        let reviews = review(&WORDS, &WORDS, &[&first, &second], false);
+        assert_eq!((reviews[0].len(), reviews[1].len()), (3, 4));
+        // cloud was the only word left on the first board, but whale was
+        // still the best guess for the second one
+        assert_eq!((reviews[0][1].before, reviews[0][1].skill), (1, 100));
+        assert_eq!(reviews[1][1].after, 1);
+        // both boards down to one word: guessing either answer is perfect
+        assert_eq!(reviews[0][2].skill, 100);
+        assert_eq!(reviews[1][3].skill, 100);
+    }
+}
@@ -0,0 +1,55 @@
+//! Speedrun mode: a clock that starts on the first keystroke of a puzzle,
+//! with a split every time a row is submitted.
+
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, Default)]
+pub struct Timer {
+    started: Option<Instant>,
+    stopped: Option<Duration>,
+    /// How long into the run each row was submitted.
+    pub splits: Vec<Duration>,
+}
+
+impl Timer {
+    /// Starts the clock, unless it's already going.
+    pub fn start(&mut self) {
+        if self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started.is_some() && self.stopped.is_none()
+    }
+
+    /// How long the run has taken so far, or in total once it's stopped.
+    pub fn elapsed(&self) -> Duration {
+        match (self.started, self.stopped) {
+            (_, Some(time)) => time,
+            (Some(started), None) => started.elapsed(),
+            (None, None) => Duration::ZERO,
+        }
+    }
+
+    pub fn split(&mut self) {
+        self.splits.push(self.elapsed());
+    }
+
+    pub fn stop(&mut self) {
+        if self.is_running() {
+            self.stopped = Some(self.elapsed());
+        }
+    }
+}
+
+/// A time as `M:SS.cc`.
+pub fn format_time(d: Duration) -> String {
+    let centis = d.as_millis() / 10;
+    format!(
+        "{}:{:02}.{:02}",
+        centis / 6000,
+        (centis / 100) % 60,
+        centis % 100
+    )
+}
@@ -1,52 +1,55 @@
-use std::{collections::HashMap, str::Chars, time::SystemTime};
+use std::time::SystemTime;
 
 use raylib::prelude::*;
-use serde_json::Value;
 
-use crate::{models::ModelStore, Screen};
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use codle::{
+    game::{Game, Mode, Settings},
+    solver::{Metric, RowReview, Suggestion},
+    stats::StatsStore,
+};
 
-use std::{ffi::CString, ptr::null_mut, time::UNIX_EPOCH, vec};
+use crate::models::ModelStore;
 
-lazy_static::lazy_static! {
-    pub static ref DICTIONARY: Vec<Value> =
-        serde_json::from_str(include_str!("./dictionary.json")).unwrap();
-}
+use std::{ffi::CString, ptr::null_mut, vec};
 
 pub struct State<'a> {
     pub rl: RaylibHandle,
     pub thread: RaylibThread,
-    pub buffer: Vec<KeyboardKey>,
-    pub guessed: Vec<String>,
-    pub words: HashMap<i64, String>,
+    pub game: Game,
+    pub stats: StatsStore,
+    /// How many of `game.finished` have been added to `stats`.
+    pub recorded: usize,
     pub models: ModelStore,
-    pub screen: Screen,
-    pub win_time: SystemTime,
+    pub end_time: SystemTime,
     pub font: Font,
     pub camera: Camera3D,
-    pub word: String,
     pub show_letters: bool,
+    /// Which answer's explainer card is showing on the end screen, if any.
+    pub card: Option<usize>,
+    /// Shows the solver's best guesses while playing.
+    pub assist: bool,
+    /// The solver's suggestions and the (puzzle, guesses) they're for, so
+    /// they're only worked out once per row.
+    suggestions: Option<((i64, usize), Vec<Suggestion>)>,
+    /// Same again for the words each board could still be.
+    candidates: Option<((i64, usize), Vec<Vec<&'static str>>)>,
+    /// Lists the words that still fit on the end screen, after a loss.
+    pub show_left: bool,
+    /// Which board's review is showing on the end screen, if any.
+    pub review: Option<usize>,
+    /// Every board's review, worked out the first time one's looked at.
+    reviews: Option<((i64, usize), Vec<Vec<RowReview>>)>,
     pub cube: Model,
     pub keys: Vec<Vec<&'a str>>,
 }
 
 impl<'a> State<'a> {
-    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(settings: Settings) -> Result<Self, Box<dyn std::error::Error>> {
         raylib::set_trace_log(TraceLogLevel::LOG_ERROR);
-        let mut buffer = Vec::new();
-        let mut guessed: Vec<String> = Vec::new();
-        let mut words = HashMap::new();
-        let (mut rl, thread) = raylib::init().size(720, 1024).title("Infinle").build();
+        let (mut rl, thread) = raylib::init().size(720, 1024).title("Codle").build();
 
         let font = load_font(&thread, include_bytes!("./Ubuntu-Regular.ttf"));
 
-        let camera = Camera3D::perspective(
-            Vector3::new(-15.0, -40.0, -100.0),
-            Vector3::new(-15.0, -40.0, 0.0),
-            Vector3::new(0.0, 1.0, 0.0),
-            60.0,
-        );
-
         let keys: Vec<Vec<&str>> = vec![
             vec!["Q", "W", "E", "R", "T", "Y", "U", "I", "O", "P"],
             vec!["A", "S", "D", "F", "G", "H", "J", "K", "L"],
@@ -58,49 +61,173 @@ impl<'a> State<'a> {
         // regular cubes
         let mesh = unsafe { Mesh::gen_mesh_cube(&thread, 15.0, 15.0, 15.0).make_weak() };
         let cube = rl.load_model_from_mesh(&thread, mesh).unwrap();
-        let mut show_letters = true;
-        let word = get_word(1 as i64, &mut words);
+        let show_letters = true;
+        let game = Game::new(settings);
+        let stats = StatsStore::load();
+        let camera = board_camera(&game.settings, 0);
 
-        let mut screen = Screen::Title;
-        let mut win_time = SystemTime::now();
+        let end_time = SystemTime::now();
 
         Ok(Self {
-            buffer,
-            guessed,
-            words,
+            game,
+            stats,
+            recorded: 0,
             models,
-            screen,
-            win_time,
+            end_time,
             rl,
             thread,
             font,
             camera,
-            word,
             show_letters,
+            card: None,
+            assist: false,
+            suggestions: None,
+            candidates: None,
+            show_left: false,
+            review: None,
+            reviews: None,
             cube,
             keys,
         })
     }
-}
 
-pub fn get_word(k: i64, words: &mut HashMap<i64, String>) -> String {
-    if let Some(w) = words.get(&k) {
-        w.clone()
-    } else {
-        let day = UNIX_EPOCH.elapsed().unwrap().as_millis() / 86400000;
-        let mut rng = StdRng::seed_from_u64(k.abs() as u64 + day as u64);
-        let num = rng.gen_range(0..DICTIONARY.len() - 1);
-        let w = DICTIONARY
-            .get(num)
-            .unwrap()
-            .as_str()
-            .unwrap()
-            .to_string()
-            .to_uppercase();
-
-        words.insert(k, w.clone());
-        w
+    pub fn frame_camera(&mut self) {
+        self.camera = board_camera(&self.game.settings, self.board_index());
+    }
+
+    /// Which board is being played, counting from the left. Only infinite
+    /// mode ever gets past the first one.
+    pub fn board_index(&self) -> usize {
+        match self.game.settings.mode {
+            Mode::Daily | Mode::Practice => 0,
+            Mode::Infinite => self.game.finished.len(),
+        }
+    }
+
+    /// Steps through the explainer cards for each answer, then back to the
+    /// results.
+    pub fn next_card(&mut self) {
+        self.review = None;
+        self.show_left = false;
+        self.card = match self.card {
+            None => Some(0),
+            Some(i) if i + 1 < self.game.boards.len() => Some(i + 1),
+            Some(_) => None,
+        };
     }
+
+    /// See [`Game::suggest`].
+    pub fn suggestions(&mut self) -> &[Suggestion] {
+        let at = (self.game.puzzle, self.game.guesses);
+        if self.suggestions.as_ref().map(|(key, _)| *key) != Some(at) {
+            self.suggestions = Some((at, self.game.suggest(Metric::Information)));
+        }
+        &self.suggestions.as_ref().unwrap().1
+    }
+
+    /// See [`Game::candidates`], for every board.
+    pub fn candidates(&mut self) -> &[Vec<&'static str>] {
+        let at = (self.game.puzzle, self.game.guesses);
+        if self.candidates.as_ref().map(|(key, _)| *key) != Some(at) {
+            let candidates = self
+                .game
+                .boards
+                .iter()
+                .map(|b| self.game.candidates(b))
+                .collect();
+            self.candidates = Some((at, candidates));
+        }
+        &self.candidates.as_ref().unwrap().1
+    }
+
+    /// See [`Game::reviews`].
+    pub fn reviews(&mut self) -> &[Vec<RowReview>] {
+        let at = (self.game.puzzle, self.game.guesses);
+        if self.reviews.as_ref().map(|(key, _)| *key) != Some(at) {
+            self.reviews = Some((at, self.game.reviews()));
+        }
+        &self.reviews.as_ref().unwrap().1
+    }
+
+    /// Steps through the review of each board, then back to the results.
+    pub fn next_review(&mut self) {
+        self.card = None;
+        self.show_left = false;
+        self.review = match self.review {
+            None => Some(0),
+            Some(i) if i + 1 < self.game.boards.len() => Some(i + 1),
+            Some(_) => None,
+        };
+    }
+
+    /// Slides the camera over towards the board being played.
+    pub fn follow_board(&mut self) {
+        let target = board_camera(&self.game.settings, self.board_index());
+        let t = (self.rl.get_frame_time() * 5.0).min(1.0);
+        self.camera.position.x += (target.position.x - self.camera.position.x) * t;
+        self.camera.target.x += (target.target.x - self.camera.target.x) * t;
+    }
+
+    /// Goes back to the title screen with a new answer for whatever the
+    /// current settings are.
+    pub fn restart(&mut self) {
+        self.game = Game::new(self.game.settings.clone());
+        self.recorded = 0;
+        self.show_letters = true;
+        self.card = None;
+        self.suggestions = None;
+        self.candidates = None;
+        self.show_left = false;
+        self.review = None;
+        self.reviews = None;
+        self.frame_camera();
+    }
+}
+
+/// How many boards go next to each other before wrapping onto a new line.
+pub fn board_columns(settings: &Settings) -> usize {
+    settings.boards.min(4)
+}
+
+/// How far apart boards are drawn along the x and y axes.
+fn board_spacing(settings: &Settings) -> (f32, f32) {
+    (
+        (settings.word_length + 2) as f32 * 16.0,
+        (settings.guess_limit() + 2) as f32 * 16.0,
+    )
+}
+
+/// Where the top right letter of a board goes. Every puzzle gets its own
+/// set of columns, so in infinite mode each puzzle sits to the right of the
+/// last one.
+pub fn board_origin(settings: &Settings, puzzle: usize, board: usize) -> Vector3 {
+    let columns = board_columns(settings);
+    let (sx, sy) = board_spacing(settings);
+    let column = puzzle * columns + board % columns;
+    let row = board / columns;
+    Vector3::new(16.0 - column as f32 * sx, 16.0 - row as f32 * sy, 0.0)
+}
+
+/// Points the camera at the middle of the `puzzle`th puzzle, backing off a
+/// bit for long words, lots of rows or lots of boards so everything still
+/// fits.
+pub fn board_camera(settings: &Settings, puzzle: usize) -> Camera3D {
+    let columns = board_columns(settings);
+    let rows = (settings.boards + columns - 1) / columns;
+    let (sx, sy) = board_spacing(settings);
+    let width = (columns - 1) as f32 * sx + (settings.word_length - 1) as f32 * 16.0;
+    let height = (rows - 1) as f32 * sy + (settings.guess_limit() - 1) as f32 * 16.0;
+
+    let first = board_origin(settings, puzzle, 0);
+    let x = first.x - width / 2.0;
+    let y = first.y - height / 2.0 - 16.0;
+    let z = -100.0 * (width / 64.0).max(height / 80.0).max(1.0);
+    Camera3D::perspective(
+        Vector3::new(x, y, z),
+        Vector3::new(x, y, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+        60.0,
+    )
 }
 
 fn load_font(_thread: &RaylibThread, fontfile: &[u8]) -> Font {
@@ -0,0 +1,302 @@
+//! Played/won counts and streaks. Every kind of game (see
+//! [`Settings::stats_key`]) keeps its own numbers, saved to a file on native
+//! builds and to the browser's local storage on the web. Speedrun personal bests are saved the
+//! same way, next to them, and so is the day rollover picked on the title
+//! screen.
+
+use std::{collections::HashMap, time::Duration};
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    game::{Finished, Mode, Settings},
+    schedule::Clock,
+};
+
+const STATS_NAME: &str = "codle_stats";
+const BESTS_NAME: &str = "codle_bests";
+const CLOCK_NAME: &str = "codle_clock";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub played: u32,
+    pub won: u32,
+    pub current_streak: u32,
+    pub max_streak: u32,
+    /// How many games were won in 1, 2, 3... guesses.
+    pub distribution: Vec<u32>,
+    /// How many games were played with hints.
+    pub hinted: u32,
+    /// The last daily puzzle that was recorded, as a day (see
+    /// [`Clock::today`](crate::schedule::Clock::today)).
+    pub last_day: Option<u64>,
+}
+
+impl Stats {
+    /// Adds a finished game. Dailies pass the day they were for: playing the
+    /// same day again doesn't count, and skipping one ends the streak.
+    /// Returns whether it was counted.
+    pub fn record(&mut self, finished: &Finished, day: Option<u64>) -> bool {
+        if let Some(day) = day {
+            match self.last_day {
+                Some(last) if day <= last => return false,
+                Some(last) if day > last + 1 => self.current_streak = 0,
+                _ => {}
+            }
+            self.last_day = Some(day);
+        }
+        self.played += 1;
+        if finished.hints > 0 {
+            self.hinted += 1;
+        }
+        if finished.won {
+            self.won += 1;
+            self.current_streak += 1;
+            self.max_streak = self.max_streak.max(self.current_streak);
+
+            let guesses = finished.guesses;
+            if self.distribution.len() < guesses {
+                self.distribution.resize(guesses, 0);
+            }
+            self.distribution[guesses - 1] += 1;
+        } else {
+            self.current_streak = 0;
+        }
+        true
+    }
+
+    pub fn win_percentage(&self) -> u32 {
+        (self.won * 100).checked_div(self.played).unwrap_or(0)
+    }
+
+    fn from_json(v: &Value) -> Self {
+        let num = |k: &str| v.get(k).and_then(|n| n.as_u64()).unwrap_or(0) as u32;
+        Self {
+            played: num("played"),
+            won: num("won"),
+            current_streak: num("current_streak"),
+            max_streak: num("max_streak"),
+            distribution: v
+                .get("distribution")
+                .and_then(|d| d.as_array())
+                .map(|d| d.iter().map(|n| n.as_u64().unwrap_or(0) as u32).collect())
+                .unwrap_or_default(),
+            hinted: num("hinted"),
+            last_day: v.get("last_day").and_then(|n| n.as_u64()),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "played": self.played,
+            "won": self.won,
+            "current_streak": self.current_streak,
+            "max_streak": self.max_streak,
+            "distribution": self.distribution,
+            "hinted": self.hinted,
+            "last_day": self.last_day,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct StatsStore {
+    stats: HashMap<String, Stats>,
+    /// Fastest wins in milliseconds, by [`Settings::stats_key`].
+    bests: HashMap<String, u64>,
+}
+
+impl StatsStore {
+    /// Loads saved stats. Anything missing or unreadable just starts from
+    /// zero.
+    pub fn load() -> Self {
+        let load = |name: &str| {
+            read(name)
+                .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+                .and_then(|v| v.as_object().cloned())
+                .unwrap_or_default()
+        };
+        let stats = load(STATS_NAME)
+            .iter()
+            .map(|(k, v)| (k.clone(), Stats::from_json(v)))
+            .collect();
+        let bests = load(BESTS_NAME)
+            .iter()
+            .filter_map(|(k, v)| Some((k.clone(), v.as_u64()?)))
+            .collect();
+        Self { stats, bests }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let stats: Map<String, Value> = self
+            .stats
+            .iter()
+            .map(|(k, v)| (k.clone(), v.to_json()))
+            .collect();
+        write(STATS_NAME, &Value::Object(stats).to_string())?;
+        let bests: Map<String, Value> = self
+            .bests
+            .iter()
+            .map(|(k, v)| (k.clone(), json!(v)))
+            .collect();
+        write(BESTS_NAME, &Value::Object(bests).to_string())
+    }
+
+    pub fn get(&self, settings: &Settings) -> Stats {
+        self.stats
+            .get(&settings.stats_key())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The fastest speedrun with these settings.
+    pub fn best_time(&self, settings: &Settings) -> Option<Duration> {
+        self.bests
+            .get(&settings.stats_key())
+            .map(|ms| Duration::from_millis(*ms))
+    }
+
+    /// Records a finished puzzle under the key for its settings. Timed wins
+    /// without any hints can be a new best time too, unless it's a daily
+    /// that's already been recorded.
+    pub fn record(&mut self, settings: &Settings, finished: &Finished) {
+        let day = (settings.mode == Mode::Daily).then_some(finished.day);
+        let counted = self
+            .stats
+            .entry(settings.stats_key())
+            .or_default()
+            .record(finished, day);
+        if !counted {
+            return;
+        }
+        if let (true, 0, Some(time)) = (finished.won, finished.hints, finished.time) {
+            let best = self.bests.entry(settings.stats_key()).or_insert(u64::MAX);
+            *best = (*best).min(time.as_millis() as u64);
+        }
+    }
+}
+
+/// The rollover saved by [`save_clock`], if there is one.
+pub fn load_clock() -> Option<Clock> {
+    let v: Value = serde_json::from_str(&read(CLOCK_NAME)?).ok()?;
+    let offset = v.get("offset_minutes")?.as_i64()?;
+    Some(Clock::new(offset as i32))
+}
+
+pub fn save_clock(clock: Clock) -> Result<(), Box<dyn std::error::Error>> {
+    let v = json!({ "offset_minutes": clock.offset_minutes });
+    write(CLOCK_NAME, &v.to_string())
+}
+
+#[cfg(not(feature = "wasm"))]
+fn path(name: &str) -> std::path::PathBuf {
+    let dir = std::env::var_os("HOME")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_default();
+    dir.join(format!(".{}.json", name))
+}
+
+#[cfg(not(feature = "wasm"))]
+fn read(name: &str) -> Option<String> {
+    std::fs::read_to_string(path(name)).ok()
+}
+
+#[cfg(not(feature = "wasm"))]
+fn write(name: &str, s: &str) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::write(path(name), s)?;
+    Ok(())
+}
+
+#[cfg(feature = "wasm")]
+extern "C" {
+    fn emscripten_run_script(script: *const std::ffi::c_char);
+    fn emscripten_run_script_string(script: *const std::ffi::c_char) -> *const std::ffi::c_char;
+}
+
+#[cfg(feature = "wasm")]
+fn read(name: &str) -> Option<String> {
+    // JSON strings are JS strings too, so this is how names get quoted
+    let script = format!("localStorage.getItem({}) || ''", Value::from(name));
+    let script = std::ffi::CString::new(script).ok()?;
+    let s = unsafe { std::ffi::CStr::from_ptr(emscripten_run_script_string(script.as_ptr())) };
+    let s = s.to_str().ok()?;
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+#[cfg(feature = "wasm")]
+fn write(name: &str, s: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let script = format!(
+        "localStorage.setItem({}, {})",
+        Value::from(name),
+        Value::from(s)
+    );
+    let script = std::ffi::CString::new(script)?;
+    unsafe { emscripten_run_script(script.as_ptr()) };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished(day: u64, won: bool) -> Finished {
+        Finished {
+            boards: Vec::new(),
+            guesses: 3,
+            won,
+            day,
+            hints: 0,
+            time: None,
+        }
+    }
+
+    #[test]
+    fn same_daily_only_counts_once() {
+        let mut stats = Stats::default();
+        assert!(stats.record(&finished(100, true), Some(100)));
+        assert!(!stats.record(&finished(100, true), Some(100)));
+        assert_eq!(stats.played, 1);
+        assert_eq!(stats.current_streak, 1);
+    }
+
+    #[test]
+    fn skipped_day_ends_the_streak() {
+        let mut stats = Stats::default();
+        stats.record(&finished(100, true), Some(100));
+        stats.record(&finished(101, true), Some(101));
+        assert_eq!(stats.current_streak, 2);
+        stats.record(&finished(103, true), Some(103));
+        assert_eq!(stats.current_streak, 1);
+        assert_eq!(stats.max_streak, 2);
+    }
+
+    #[test]
+    fn other_modes_count_every_game() {
+        let mut stats = Stats::default();
+        stats.record(&finished(100, true), None);
+        stats.record(&finished(100, false), None);
+        assert_eq!((stats.played, stats.won, stats.current_streak), (2, 1, 0));
+        assert_eq!(stats.last_day, None);
+    }
+
+    #[test]
+    fn best_times_are_kept_per_kind_of_game() {
+        let mut store = StatsStore::default();
+        let easy = Settings {
+            max_guesses: 8,
+            ..Default::default()
+        };
+        let mut fast = finished(100, true);
+        fast.time = Some(Duration::from_secs(10));
+        store.record(&easy, &fast);
+        assert_eq!(store.best_time(&easy), Some(Duration::from_secs(10)));
+        assert_eq!(store.best_time(&Settings::default()), None);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut stats = Stats::default();
+        stats.record(&finished(100, true), Some(100));
+        assert_eq!(Stats::from_json(&stats.to_json()), stats);
+    }
+}
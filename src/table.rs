@@ -0,0 +1,107 @@
+//! Hash tables for looking words up in a [`Dictionary`]. The bundled
+//! dictionaries get theirs filled in ahead of time by `build.rs` (which
+//! includes this file too, so both sides hash the same way), leaving nothing
+//! to hash or parse at startup.
+//!
+//! A table is a power of two of slots, at most half full, apart from the
+//! empty table which is a single empty slot. Each slot is 0 if it's empty,
+//! or one more than the index of the word that hashed there. Collisions go
+//! in the next free slot along.
+//!
+//! [`Dictionary`]: crate::dictionary::Dictionary
+
+/// FNV-1a over the word, ignoring case.
+pub fn hash(word: &str) -> u64 {
+    word.bytes().fold(0xcbf29ce484222325, |h, b| {
+        (h ^ b.to_ascii_lowercase() as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// The index of `word`, where `is(i, word)` says whether the `i`th word the
+/// table was built from is `word`.
+pub fn find(slots: &[u32], word: &str, is: impl Fn(usize, &str) -> bool) -> Option<usize> {
+    let mask = slots.len() - 1;
+    let mut slot = hash(word) as usize & mask;
+    loop {
+        match slots[slot] {
+            0 => return None,
+            i if is(i as usize - 1, word) => return Some(i as usize - 1),
+            _ => slot = (slot + 1) & mask,
+        }
+    }
+}
+
+/// Puts word number `i` in the first free slot for it.
+pub fn insert(slots: &mut [u32], word: &str, i: usize) {
+    let mask = slots.len() - 1;
+    let mut slot = hash(word) as usize & mask;
+    while slots[slot] != 0 {
+        slot = (slot + 1) & mask;
+    }
+    slots[slot] = i as u32 + 1;
+}
+
+/// A table just big enough for `words`, which shouldn't have any
+/// duplicates.
+pub fn build<'a>(words: impl ExactSizeIterator<Item = &'a str>) -> Vec<u32> {
+    let mut slots = vec![0; (words.len() * 2).next_power_of_two()];
+    for (i, word) in words.enumerate() {
+        insert(&mut slots, word, i);
+    }
+    slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(slots: &[u32], words: &[&str], word: &str) -> Option<usize> {
+        find(slots, word, |i, word| words[i].eq_ignore_ascii_case(word))
+    }
+
+    #[test]
+    fn finds_every_word_in_any_case() {
+        let words = ["array", "error", "while", "cloud", "alter"];
+        let slots = build(words.iter().copied());
+        assert_eq!(slots.len(), 16);
+        for (i, word) in words.iter().enumerate() {
+            assert_eq!(lookup(&slots, &words, word), Some(i));
+            assert_eq!(lookup(&slots, &words, &word.to_uppercase()), Some(i));
+        }
+        assert_eq!(lookup(&slots, &words, "where"), None);
+    }
+
+    #[test]
+    fn collisions_go_in_the_next_slot() {
+        let pool = [
+            "array", "error", "while", "cloud", "alter", "where", "yield", "class", "const",
+        ];
+        // nine words and eight slots, so two of them must start in the same one
+        let home = |word: &str| hash(word) as usize & 7;
+        let (a, b) = pool
+            .iter()
+            .enumerate()
+            .find_map(|(i, a)| {
+                pool[i + 1..]
+                    .iter()
+                    .find(|b| home(b) == home(a))
+                    .map(|b| (*a, *b))
+            })
+            .unwrap();
+        let words = [a, b];
+        let mut slots = vec![0; 8];
+        insert(&mut slots, a, 0);
+        insert(&mut slots, b, 1);
+        assert_eq!(slots[home(a)], 1);
+        assert_eq!(slots[(home(a) + 1) & 7], 2);
+        assert_eq!(lookup(&slots, &words, a), Some(0));
+        assert_eq!(lookup(&slots, &words, b), Some(1));
+    }
+
+    #[test]
+    fn empty_table() {
+        let slots = build(std::iter::empty());
+        assert_eq!(slots, [0]);
+        assert_eq!(lookup(&slots, &[], "array"), None);
+    }
+}
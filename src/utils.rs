@@ -1,5 +1,17 @@
+use std::time::Duration;
+
 use raylib::prelude::*;
 
+use codle::{
+    dictionary::{self, Tag},
+    game::{Input, Row},
+};
+
+use crate::{
+    models::{ModelStore, Tile},
+    GREEN,
+};
+
 pub fn draw_text_centered<A>(
     rl: &mut A,
     font: &Font,
@@ -23,39 +35,236 @@ pub fn draw_text_centered<A>(
     );
 }
 
-pub fn push_valid_word(buffer: &mut Vec<KeyboardKey>, k: KeyboardKey) {
-    if buffer.len() < 5 {
-        match k {
-            KeyboardKey::KEY_A
-            | KeyboardKey::KEY_B
-            | KeyboardKey::KEY_C
-            | KeyboardKey::KEY_D
-            | KeyboardKey::KEY_E
-            | KeyboardKey::KEY_F
-            | KeyboardKey::KEY_G
-            | KeyboardKey::KEY_H
-            | KeyboardKey::KEY_I
-            | KeyboardKey::KEY_J
-            | KeyboardKey::KEY_K
-            | KeyboardKey::KEY_L
-            | KeyboardKey::KEY_M
-            | KeyboardKey::KEY_N
-            | KeyboardKey::KEY_O
-            | KeyboardKey::KEY_P
-            | KeyboardKey::KEY_Q
-            | KeyboardKey::KEY_R
-            | KeyboardKey::KEY_S
-            | KeyboardKey::KEY_T
-            | KeyboardKey::KEY_U
-            | KeyboardKey::KEY_V
-            | KeyboardKey::KEY_W
-            | KeyboardKey::KEY_X
-            | KeyboardKey::KEY_Y
-            | KeyboardKey::KEY_Z => {
-                buffer.push(k);
+/// Everything needed to draw one board.
+pub struct BoardView<'a> {
+    pub rows: &'a [Row],
+    /// What's being typed into the next row, and whether it's invalid.
+    pub buffer: Option<(&'a str, bool)>,
+    /// Shown under the board when it was lost.
+    pub answer: Option<&'a str>,
+    /// Letters given away by hints, filled in wherever the buffer doesn't
+    /// cover them yet.
+    pub hints: &'a [(usize, char)],
+    pub word_length: usize,
+    pub max_guesses: usize,
+}
+
+/// Draws a board with its top right letter at `origin` (the camera looks down
+/// +z, so letters further along the word have a lower x).
+pub fn draw_board<D>(
+    d: &mut D,
+    models: &ModelStore,
+    cube: &Model,
+    show_letters: bool,
+    board: &BoardView,
+    origin: Vector3,
+) where
+    D: RaylibDraw3D,
+{
+    let draw_letter = |d: &mut D, tile: Tile, ch: char, pos: Vector3| {
+        if let Some(lette) = models.letter(tile, ch) {
+            let l = match show_letters {
+                true => &lette.1,
+                false => &lette.0,
+            };
+            d.draw_model(l, pos, 1.0, Color::WHITE);
+        }
+    };
+
+    for guess in 0..board.max_guesses {
+        for letter in 0..board.word_length {
+            let pos = Vector3::new(
+                origin.x - (letter as f32 * 16.0),
+                origin.y - (guess as f32 * 16.0),
+                origin.z,
+            );
+
+            if let Some(row) = board.rows.get(guess) {
+                if let Some(ch) = row.word.chars().nth(letter) {
+                    let tile = row
+                        .feedback
+                        .get(letter)
+                        .map(|f| Tile::from(*f))
+                        .unwrap_or(Tile::Plain);
+                    draw_letter(d, tile, ch, pos);
+                }
+            } else if let Some(ch) = board
+                .buffer
+                .filter(|_| guess == board.rows.len())
+                .and_then(|(buffer, _)| buffer.chars().nth(letter))
+            {
+                let invalid = board.buffer.map(|(_, invalid)| invalid).unwrap_or(false);
+                let tile = if invalid { Tile::Red } else { Tile::Plain };
+                draw_letter(d, tile, ch, pos);
+            } else if let Some((_, ch)) = board
+                .hints
+                .iter()
+                .filter(|_| board.buffer.is_some() && guess == board.rows.len())
+                .find(|(p, _)| *p == letter)
+            {
+                draw_letter(d, Tile::Green, *ch, pos);
+            } else {
+                d.draw_model(cube, pos, 1.0, Color::new(24, 24, 27, 255))
+            }
+        }
+    }
+
+    if let Some(answer) = board.answer {
+        for (n, ch) in answer.chars().enumerate() {
+            if let Some(lette) = models.letter(Tile::Plain, ch) {
+                d.draw_model(
+                    &lette.1,
+                    Vector3::new(
+                        origin.x - (n as f32 * 16.0),
+                        origin.y - (board.max_guesses as f32 * 16.0),
+                        origin.z,
+                    ),
+                    1.0,
+                    Color::WHITE,
+                );
+            }
+        }
+    }
+}
+
+/// Draws a line of centered text that can be clicked, and returns whether it
+/// was clicked this frame.
+pub fn draw_option(
+    d: &mut RaylibDrawHandle,
+    font: &Font,
+    font_size: f32,
+    text: &str,
+    width: i32,
+    y: f32,
+) -> bool {
+    draw_text_centered(d, font, font_size, text, width, y, 1.0);
+
+    let w = measure_text_ex(&font, &text, font_size, 3.0);
+    let x = (width / 2) as f32 - (w.x / 2.0);
+    let mx = d.get_touch_x() as f32;
+    let my = d.get_touch_y() as f32;
+
+    d.is_gesture_detected(Gesture::GESTURE_TAP)
+        && mx >= x
+        && mx <= x + w.x
+        && my >= y
+        && my <= y + font_size
+}
+
+/// Splits text into lines no wider than `max_width`, breaking between words.
+/// Line breaks already in the text are kept.
+pub fn wrap_text(font: &Font, font_size: f32, text: &str, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+            if !line.is_empty() && measure_text_ex(font, &candidate, font_size, 3.0).x > max_width {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            } else {
+                line = candidate;
             }
-            _ => {}
-        };
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Draws the card explaining an answer after a game: which languages use it,
+/// what it does and a bit of example code. Text is laid out in a column
+/// `width` wide starting at `x`.
+pub fn draw_card(
+    d: &mut RaylibDrawHandle,
+    font: &Font,
+    font_size: f32,
+    word: &str,
+    x: f32,
+    mut y: f32,
+    width: f32,
+    alpha: f32,
+) {
+    let entries = dictionary::lookup(word);
+    let screen_width = d.get_screen_width();
+    draw_text_centered(d, font, font_size, word, screen_width, y, alpha);
+    y += font_size * 1.5;
+
+    let small = font_size * 0.6;
+    let mut tags: Vec<Tag> = entries
+        .iter()
+        .flat_map(|e| e.tags.iter().copied())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    let names: Vec<&str> = tags
+        .iter()
+        .filter(|t| **t != Tag::General)
+        .map(|t| t.name())
+        .collect();
+    let description = entries.iter().find_map(|e| e.description.as_deref());
+    let example = entries.iter().find_map(|e| e.example.as_deref());
+
+    let mut text = Vec::new();
+    if !names.is_empty() {
+        text.push(names.join(", "));
+    }
+    text.push(
+        description
+            .unwrap_or("No notes on this one yet.")
+            .to_string(),
+    );
+    for paragraph in text {
+        for line in wrap_text(font, small, &paragraph, width) {
+            draw_text_centered(d, font, small, &line, screen_width, y, alpha);
+            y += small * 1.2;
+        }
+        y += small * 0.6;
+    }
+
+    // code keeps its indentation, so it's left aligned instead
+    if let Some(example) = example {
+        let code = font_size * 0.5;
+        for line in example.lines() {
+            d.draw_text_ex(font, line, Vector2::new(x, y), code, 3.0, GREEN.fade(alpha));
+            y += code * 1.2;
+        }
+    }
+}
+
+/// Formats a duration as `HH:MM:SS`.
+pub fn format_countdown(d: Duration) -> String {
+    let secs = d.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60
+    )
+}
+
+/// Turns a physical keypress into something the game understands.
+pub fn key_input(k: KeyboardKey) -> Option<Input> {
+    match k {
+        KeyboardKey::KEY_ENTER => Some(Input::Submit),
+        KeyboardKey::KEY_BACKSPACE => Some(Input::Backspace),
+        KeyboardKey::KEY_SLASH => Some(Input::Hint),
+        _ => get_letter(&k)
+            .chars()
+            .next()
+            .filter(|ch| ch.is_ascii_alphabetic())
+            .map(Input::Letter),
+    }
+}
+
+/// Same as [`key_input`] but for the on-screen keyboard.
+pub fn tapped_input(key: &str) -> Option<Input> {
+    match key {
+        "^" => Some(Input::Submit),
+        "<" => Some(Input::Backspace),
+        _ => key.chars().next().map(Input::Letter),
     }
 }
 
@@ -92,36 +301,3 @@ pub fn get_letter(key: &KeyboardKey) -> &str {
         _ => "?",
     }
 }
-
-pub fn get_key(key: &str) -> KeyboardKey {
-    match key {
-        "A" => KeyboardKey::KEY_A,
-        "B" => KeyboardKey::KEY_B,
-        "C" => KeyboardKey::KEY_C,
-        "D" => KeyboardKey::KEY_D,
-        "E" => KeyboardKey::KEY_E,
-        "F" => KeyboardKey::KEY_F,
-        "G" => KeyboardKey::KEY_G,
-        "H" => KeyboardKey::KEY_H,
-        "I" => KeyboardKey::KEY_I,
-        "J" => KeyboardKey::KEY_J,
-        "K" => KeyboardKey::KEY_K,
-        "L" => KeyboardKey::KEY_L,
-        "M" => KeyboardKey::KEY_M,
-        "N" => KeyboardKey::KEY_N,
-        "O" => KeyboardKey::KEY_O,
-        "P" => KeyboardKey::KEY_P,
-        "Q" => KeyboardKey::KEY_Q,
-        "R" => KeyboardKey::KEY_R,
-        "S" => KeyboardKey::KEY_S,
-        "T" => KeyboardKey::KEY_T,
-        "U" => KeyboardKey::KEY_U,
-        "V" => KeyboardKey::KEY_V,
-        "W" => KeyboardKey::KEY_W,
-        "X" => KeyboardKey::KEY_X,
-        "Y" => KeyboardKey::KEY_Y,
-        "Z" => KeyboardKey::KEY_Z,
-
-        _ => KeyboardKey::KEY_NULL,
-    }
-}